@@ -0,0 +1,131 @@
+//! Multi-byte UTF-8 colony glyphs. The grid holds one byte per cell, so each
+//! distinct non-ASCII character on a board stands in as a byte from 0x80 up
+//! while the board evolves, and is expanded back to UTF-8 on output.
+
+/// Most distinct non-ASCII characters one board can use.
+pub(crate) const MAX_GLYPHS: usize = 128;
+
+/// The non-ASCII characters of one board, indexed by stand-in byte - 0x80.
+pub(crate) struct Glyphs {
+    chars: [([u8; 4], usize); MAX_GLYPHS],
+    count: usize,
+}
+
+impl Glyphs {
+    pub(crate) fn new() -> Self {
+        Glyphs {
+            chars: [([0; 4], 0); MAX_GLYPHS],
+            count: 0,
+        }
+    }
+
+    /// True if no character has been assigned a stand-in byte.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Rewrites `text` in place with every multi-byte character replaced by its
+    /// stand-in byte, and returns the new length. Returns `None`, leaving
+    /// `text` untouched, if it is not valid UTF-8 or has more than
+    /// `MAX_GLYPHS` distinct non-ASCII characters.
+    pub(crate) fn compact(&mut self, text: &mut [u8]) -> Option<usize> {
+        core::str::from_utf8(text).ok()?;
+
+        let mut read = 0usize;
+        while read < text.len() {
+            let len = char_len(text[read]);
+            if len > 1 && self.stand_in(&text[read..read + len]).is_none() {
+                self.count = 0;
+                return None;
+            }
+            read += len;
+        }
+
+        let (mut read, mut write) = (0usize, 0usize);
+        while read < text.len() {
+            let len = char_len(text[read]);
+            text[write] = match len {
+                1 => text[read],
+                _ => self.stand_in(&text[read..read + len])?,
+            };
+            read += len;
+            write += 1;
+        }
+        Some(write)
+    }
+
+    /// Returns the UTF-8 bytes that grid byte `cell` stands for.
+    pub(crate) fn expand(&self, cell: u8) -> ([u8; 4], usize) {
+        match (cell as usize).checked_sub(0x80) {
+            Some(i) if i < self.count => self.chars[i],
+            _ => ([cell, 0, 0, 0], 1),
+        }
+    }
+
+    /// Returns the Unicode code point of the character grid byte `cell` stands
+    /// for, or `cell` itself if it is not part of valid UTF-8.
+    pub(crate) fn code_point(&self, cell: u8) -> u32 {
+        let (bytes, len) = self.expand(cell);
+        core::str::from_utf8(&bytes[..len])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .map_or(cell as u32, |c| c as u32)
+    }
+
+    /// Returns the stand-in byte for one encoded character, assigning the next
+    /// free one if it is new.
+    fn stand_in(&mut self, encoded: &[u8]) -> Option<u8> {
+        let known = self.chars[..self.count]
+            .iter()
+            .position(|(bytes, len)| &bytes[..*len] == encoded);
+        let i = match known {
+            Some(i) => i,
+            None if self.count < MAX_GLYPHS => {
+                let mut bytes = [0u8; 4];
+                bytes[..encoded.len()].copy_from_slice(encoded);
+                self.chars[self.count] = (bytes, encoded.len());
+                self.count += 1;
+                self.count - 1
+            }
+            None => return None,
+        };
+        Some(0x80 + i as u8)
+    }
+}
+
+/// Length of the UTF-8 sequence starting with `lead`, which must begin a
+/// character of valid UTF-8.
+fn char_len(lead: u8) -> usize {
+    match lead {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compact_and_expand() {
+        let mut glyphs = Glyphs::new();
+        let mut text = [0u8; 13];
+        text.copy_from_slice("é😀\né😀".as_bytes());
+        assert_eq!(glyphs.compact(&mut text), Some(5));
+        assert_eq!(&text[..5], &[0x80, 0x81, b'\n', 0x80, 0x81]);
+        assert_eq!(glyphs.expand(0x80), ([0xc3, 0xa9, 0, 0], 2));
+        assert_eq!(glyphs.expand(0x81).1, 4);
+        assert_eq!(glyphs.expand(b'O'), ([b'O', 0, 0, 0], 1));
+    }
+
+    #[test]
+    fn test_compact_rejects_invalid_utf8() {
+        let mut glyphs = Glyphs::new();
+        let mut text = [b'O', 0xe9, b'O'];
+        assert_eq!(glyphs.compact(&mut text), None);
+        assert_eq!(text, [b'O', 0xe9, b'O']);
+        assert!(glyphs.is_empty());
+    }
+}
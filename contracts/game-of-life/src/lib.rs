@@ -1,268 +1,5467 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Bytes, Env, String};
+extern crate alloc;
+
+mod glyphs;
+mod patterns;
+mod rules;
+
+use core::ops::Range;
+use glyphs::Glyphs;
+use rules::{
+    count_within, get_dominant_type, get_neighbor_info, leading_types, neighbor_types_at,
+    step_grid, transition, Dominance, Rule, Tally, TieBreak, DIAGONAL, ORTHOGONAL,
+};
+pub use rules::{BoundaryMode, Neighborhood};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, symbol_short, Bytes,
+    Env, IntoVal, Map, String, Val, Vec,
+};
 
 const MAX_BOARD_SIZE: usize = 100_000;
 
-/// Returns (neighbor_count, array of neighbor cell types, count of types)
-fn get_neighbor_info(grid: &[u8], x: i32, y: i32, width: usize, height: usize) -> (u32, [u8; 8], usize) {
-    let mut types = [0u8; 8];
-    let mut count = 0usize;
+/// Largest board, in cells, that `trace_step` will produce a trace for.
+const MAX_TRACE_CELLS: usize = 1_024;
+
+/// Most boards `enumerate_successors` returns; larger `limit`s are clamped.
+const MAX_SUCCESSORS: u32 = 64;
+
+/// Percent chance that a dead cell touching the aggregate sticks to it in
+/// `next_generation_dla`.
+const DLA_STICK_PERCENT: u64 = 50;
+
+/// Heat level below which a thermal cell goes cold and dies.
+const THERMAL_THRESHOLD: u32 = 2;
+
+/// Most generations `next_generation_n`, `submit_pattern`, `find_period`,
+/// `population_series`, `step_n_alternating`, `fingerprint`, `is_spark`,
+/// `step_until_calm`, `first_contact`, `generations_until_pattern`,
+/// `oscillator_canonical`, and each `find_loop_seed` attempt will run in a
+/// single call.
+const MAX_STEPS: u32 = 1_000;
+
+/// Most generations `find_loop_seed` runs across all of its attempts.
+const MAX_SEARCH_STEPS: u32 = 10 * MAX_STEPS;
+
+/// Oldest age `next_generation_aging` tracks; larger `max_age`s are clamped.
+const MAX_AGE: u32 = u8::MAX as u32;
+
+/// Largest neighbor radius `next_generation_range` accepts: its 48 neighbors
+/// are the most a `u64` birth or survival mask can describe.
+const MAX_RADIUS: u32 = 3;
+
+/// Remaining lifetime, in ledgers, below which a write to the stored board or
+/// its other persistent state extends it (about a day at five seconds per
+/// ledger).
+const BOARD_TTL_THRESHOLD: u32 = 17_280;
+
+/// Lifetime, in ledgers, a persistent entry is extended to on a write (about
+/// 30 days).
+const BOARD_TTL_EXTEND: u32 = 518_400;
+
+/// Keys for the state the contract keeps in storage.
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    /// The board driven by `init_board` and `step`, in persistent storage.
+    Board,
+    /// How many generations the stored board has advanced since `init_board`,
+    /// in persistent storage.
+    Generation,
+    /// The longest-lived board submitted to `submit_pattern`, and its lifespan,
+    /// in persistent storage.
+    Record,
+    /// The generation being computed across calls by `advance_slice`, in
+    /// persistent storage.
+    Slice,
+}
+
+/// A generation of the stored board in progress: the board it starts from,
+/// the cells computed so far, row by row, and the next row to compute.
+#[contracttype]
+#[derive(Clone)]
+struct SliceState {
+    source: String,
+    pending: Bytes,
+    next_row: u32,
+}
+
+/// Errors a contract call can fail with.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    /// The board is empty, oversized, or has no cells.
+    Unusable = 1,
+    /// A row's length differs from the first row's.
+    RaggedRows = 2,
+    /// The resulting board would be longer than `MAX_BOARD_SIZE` bytes.
+    ResultTooLarge = 3,
+    /// A colony's character has no RLE tag that reads back as that colony.
+    NoRleTag = 4,
+    /// The neighbor radius is larger than `MAX_RADIUS`.
+    UnsupportedRadius = 5,
+    /// Two boards that must have the same dimensions do not.
+    DimensionMismatch = 6,
+    /// A patch entry is incomplete, points outside the board, or holds a
+    /// newline.
+    BadPatch = 7,
+    /// The wall character is a dead cell or a row separator.
+    BadWall = 8,
+    /// A rule lookup table is the wrong length or holds entries other than 0
+    /// and 1.
+    BadLookupTable = 9,
+}
+
+/// Returns true if any cell of colony `a` has a colony `b` cell among its
+/// eight neighbors.
+fn colonies_touch(grid: &[u8], width: usize, height: usize, a: u32, b: u32) -> bool {
+    for y in 0..height {
+        for x in 0..width {
+            if grid[y * width + x] == b' ' || grid[y * width + x] as u32 != a {
+                continue;
+            }
+            let (_, neighbor_types, type_count) =
+                get_neighbor_info(grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+            if neighbor_types[..type_count].iter().any(|&t| t as u32 == b) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Returns true if the `pattern_width`x`pattern_height` `pattern` appears
+/// anywhere in the grid as a sub-rectangle, matching every cell exactly.
+fn contains_pattern(
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    pattern: &[u8],
+    pattern_width: usize,
+    pattern_height: usize,
+) -> bool {
+    if pattern_width > width || pattern_height > height {
+        return false;
+    }
+    (0..=height - pattern_height).any(|oy| {
+        (0..=width - pattern_width).any(|ox| {
+            (0..pattern_height).all(|py| {
+                let row = (oy + py) * width + ox;
+                grid[row..row + pattern_width]
+                    == pattern[py * pattern_width..(py + 1) * pattern_width]
+            })
+        })
+    })
+}
+
+/// Returns the RLE tag for a cell: `b` for dead, `o` for the default `O`
+/// colony, and the cell byte itself for any other colony.
+fn rle_tag(cell: u8) -> u8 {
+    match cell {
+        b' ' => b'b',
+        b'O' => b'o',
+        c => c,
+    }
+}
+
+/// Returns true if the cell can be written as an RLE tag that reads back as
+/// the same cell: dead, `O`, or any other letter but `b` and `o`.
+fn rle_taggable(cell: u8) -> bool {
+    cell == b' ' || cell == b'O' || (cell.is_ascii_alphabetic() && cell != b'b' && cell != b'o')
+}
+
+/// Emits `n` in decimal.
+fn emit_number(mut n: usize, emit: &mut impl FnMut(u8)) {
+    let mut digits = [0u8; 20];
+    let mut len = 0usize;
+    loop {
+        digits[len] = b'0' + (n % 10) as u8;
+        len += 1;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    for &d in digits[..len].iter().rev() {
+        emit(d);
+    }
+}
+
+/// Emits one RLE run, omitting the count when it is 1.
+fn emit_run(run: usize, tag: u8, emit: &mut impl FnMut(u8)) {
+    if run > 1 {
+        emit_number(run, emit);
+    }
+    emit(tag);
+}
+
+/// Streams the Life RLE encoding of a grid: an `x = W, y = H` header line, then
+/// runs of `b` (dead) and colony tags, `$` between rows, and a `!` terminator.
+/// Trailing dead cells and trailing empty rows are omitted, as is standard.
+fn encode_rle(grid: &[u8], width: usize, height: usize, emit: &mut impl FnMut(u8)) {
+    for &b in b"x = " {
+        emit(b);
+    }
+    emit_number(width, emit);
+    for &b in b", y = " {
+        emit(b);
+    }
+    emit_number(height, emit);
+    emit(b'\n');
+
+    let mut pending_rows = 0usize;
+    for y in 0..height {
+        let row = &grid[y * width..(y + 1) * width];
+        let mut x = 0usize;
+        while x < width {
+            let cell = row[x];
+            let mut run = 1usize;
+            while x + run < width && row[x + run] == cell {
+                run += 1;
+            }
+            if cell != b' ' || x + run < width {
+                if pending_rows > 0 {
+                    emit_run(pending_rows, b'$', emit);
+                    pending_rows = 0;
+                }
+                emit_run(run, rle_tag(cell), emit);
+            }
+            x += run;
+        }
+        pending_rows += 1;
+    }
+    emit(b'!');
+}
+
+/// Reads the number following `key` (and any spaces or `=`) in an RLE header
+/// line such as `x = 3, y = 3, rule = B3/S23`.
+fn rle_header_value(line: &[u8], key: u8) -> Option<usize> {
+    let start = line.iter().position(|&b| b == key)? + 1;
+    let digits = line[start..]
+        .iter()
+        .skip_while(|&&b| b == b' ' || b == b'=')
+        .take_while(|b| b.is_ascii_digit());
+    let mut value = None;
+    for &d in digits {
+        value = Some(
+            value
+                .unwrap_or(0usize)
+                .checked_mul(10)?
+                .checked_add((d - b'0') as usize)?,
+        );
+    }
+    value.filter(|&v| v <= MAX_BOARD_SIZE)
+}
+
+/// Walks the body of an RLE pattern, calling `visit(x, y, run, cell)` for each
+/// run of live cells, and returns the `(width, height)` the runs cover. `b` is
+/// dead, `o` is the `O` colony, and any other printable byte is its own colony.
+/// Returns `None` for malformed or oversized patterns.
+fn walk_rle(
+    body: &[u8],
+    visit: &mut impl FnMut(usize, usize, usize, u8),
+) -> Option<(usize, usize)> {
+    let (mut x, mut y) = (0usize, 0usize);
+    let (mut width, mut height) = (0usize, 0usize);
+    let mut count = 0usize;
+    for &b in body {
+        match b {
+            b'0'..=b'9' => count = count * 10 + (b - b'0') as usize,
+            b'!' => break,
+            b'$' => {
+                y += count.max(1);
+                x = 0;
+                count = 0;
+            }
+            b'\n' | b'\r' | b' ' | b'\t' => {}
+            tag if tag.is_ascii_graphic() => {
+                let run = count.max(1);
+                if tag != b'b' {
+                    visit(x, y, run, if tag == b'o' { b'O' } else { tag });
+                }
+                x += run;
+                width = width.max(x);
+                height = height.max(y + 1);
+                count = 0;
+            }
+            _ => return None,
+        }
+        if count > MAX_BOARD_SIZE || x > MAX_BOARD_SIZE || y > MAX_BOARD_SIZE {
+            return None;
+        }
+    }
+    Some((width, height))
+}
+
+/// Decodes an RLE pattern into a grid and returns it with its `(width, height)`.
+/// Leading `#` comment lines are skipped; an `x = W, y = H` header, if present,
+/// sets the minimum dimensions, which otherwise come from the runs themselves.
+fn decode_rle(text: &[u8]) -> Option<(alloc::vec::Vec<u8>, usize, usize)> {
+    let mut body = text;
+    let mut header = (0usize, 0usize);
+    while !body.is_empty() {
+        let end = body
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(body.len(), |i| i + 1);
+        let line = &body[..end];
+        match line.iter().find(|&&b| !is_blank(b)) {
+            Some(b'#') => body = &body[end..],
+            Some(b'x') => {
+                header = (rle_header_value(line, b'x')?, rle_header_value(line, b'y')?);
+                body = &body[end..];
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    let (runs_width, runs_height) = walk_rle(body, &mut |_, _, _, _| {})?;
+    let width = header.0.max(runs_width);
+    let height = header.1.max(runs_height);
+    if width == 0 || height == 0 || width.checked_mul(height)? + height - 1 > MAX_BOARD_SIZE {
+        return None;
+    }
+
+    let mut grid = alloc::vec![b' '; width * height];
+    walk_rle(body, &mut |x, y, run, cell| {
+        grid[y * width + x..y * width + x + run].fill(cell);
+    })?;
+    Some((grid, width, height))
+}
+
+/// Returns the coordinate sums `(sum_x, sum_y, count)` of a colony's cells.
+fn colony_sums(grid: &[u8], width: usize, height: usize, colony: u32) -> (i64, i64, i64) {
+    let mut sums = (0i64, 0i64, 0i64);
+    for y in 0..height {
+        for x in 0..width {
+            let c = grid[y * width + x];
+            if c != b' ' && c as u32 == colony {
+                sums.0 += x as i64;
+                sums.1 += y as i64;
+                sums.2 += 1;
+            }
+        }
+    }
+    sums
+}
+
+/// Divides `sum` by a nonzero `n`, rounding half up.
+fn round_div(sum: u64, n: u64) -> u64 {
+    (sum + n / 2) / n
+}
+
+/// 64-bit FNV-1a hash of `bytes`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Returns true for the whitespace bytes that mark a dead cell: space, tab,
+/// vertical tab, and form feed.
+fn is_blank(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | 0x0b | 0x0c)
+}
+
+/// Copies `board` into `grid` with the row separators stripped and returns its
+/// `(width, height)`. Any blank byte is stored as a space, so the rest of the
+/// contract only has to recognize `b' '` as dead. Fails for empty, oversized,
+/// or cell-less boards, for boards longer than `grid`, and for rows whose
+/// length differs from the first's.
+fn load_grid(board: &String, grid: &mut [u8]) -> Result<(usize, usize), Error> {
+    let len = board.len() as usize;
+    if len == 0 || len > MAX_BOARD_SIZE || len > grid.len() {
+        return Err(Error::Unusable);
+    }
+    board.copy_into_slice(&mut grid[..len]);
+    parse_grid(grid, len)
+}
+
+/// Like `load_grid`, but each multi-byte UTF-8 character is one cell, stored
+/// as the stand-in byte `glyphs` assigns it. Boards that are not valid UTF-8
+/// load byte by byte, as `load_grid` would.
+fn load_glyph_grid(
+    board: &String,
+    grid: &mut [u8],
+    glyphs: &mut Glyphs,
+) -> Result<(usize, usize), Error> {
+    let len = board.len() as usize;
+    if len == 0 || len > MAX_BOARD_SIZE || len > grid.len() {
+        return Err(Error::Unusable);
+    }
+    board.copy_into_slice(&mut grid[..len]);
+    let len = match grid[..len].is_ascii() {
+        true => len,
+        false => glyphs.compact(&mut grid[..len]).unwrap_or(len),
+    };
+    parse_grid(grid, len)
+}
+
+/// Strips the row separators from the first `len` bytes of `grid` in place and
+/// returns its `(width, height)`, as described for `load_grid`. A `\r\n` pair
+/// separates rows just as `\n` does.
+fn parse_grid(grid: &mut [u8], len: usize) -> Result<(usize, usize), Error> {
+    let mut width: usize = 0;
+    let mut height: usize = 0;
+    let mut current_width: usize = 0;
+    let mut idx = 0usize;
+
+    // Newlines are dropped in place; `idx` never overtakes the read position.
+    for i in 0..len {
+        let b = grid[i];
+        if b == b'\r' && i + 1 < len && grid[i + 1] == b'\n' {
+            continue;
+        }
+        if b == b'\n' {
+            if height == 0 {
+                width = current_width;
+            } else if current_width != width {
+                return Err(Error::RaggedRows);
+            }
+            height += 1;
+            current_width = 0;
+        } else {
+            grid[idx] = if is_blank(b) { b' ' } else { b };
+            idx += 1;
+            current_width += 1;
+        }
+    }
+    if current_width > 0 {
+        if height == 0 {
+            width = current_width;
+        } else if current_width != width {
+            return Err(Error::RaggedRows);
+        }
+        height += 1;
+    }
+
+    if width == 0 || height == 0 || width * height + height - 1 > MAX_BOARD_SIZE {
+        return Err(Error::Unusable);
+    }
+
+    Ok((width, height))
+}
+
+/// Advances `board` one generation under `rule` and `boundary` with random
+/// tie-breaks, handling multi-byte characters and errors as `evolve_glyph_board`
+/// does.
+fn evolve_with_rule(env: &Env, board: String, rule: Rule, boundary: BoundaryMode) -> String {
+    let (mut grid, mut next) = (alloc::vec::Vec::new(), alloc::vec::Vec::new());
+    evolve_glyph_board(env, board, rule, boundary, &mut grid, &mut next)
+}
+
+/// Advances `board` one generation under `rule` and `boundary` the way
+/// `next_generation` does, multi-byte characters included, loading it into
+/// `grid` and stepping it into `next`. Both buffers are resized to fit, so
+/// callers evolving many boards can reuse them. Unusable boards are returned
+/// unchanged; ragged ones fail with `Error::RaggedRows`, and results over
+/// `MAX_BOARD_SIZE` bytes with `Error::ResultTooLarge`.
+fn evolve_glyph_board(
+    env: &Env,
+    board: String,
+    rule: Rule,
+    boundary: BoundaryMode,
+    grid: &mut alloc::vec::Vec<u8>,
+    next: &mut alloc::vec::Vec<u8>,
+) -> String {
+    grid.clear();
+    grid.resize(board.len() as usize, 0);
+    let mut glyphs = Glyphs::new();
+    let (width, height) = match load_glyph_grid(&board, grid, &mut glyphs) {
+        Ok(dimensions) => dimensions,
+        Err(Error::RaggedRows) => panic_with_error!(env, Error::RaggedRows),
+        Err(_) => return board,
+    };
+
+    next.clear();
+    next.resize(width * height, 0);
+    step_grid(
+        env,
+        grid,
+        next,
+        width,
+        height,
+        rule,
+        boundary,
+        &mut Dominance::random(),
+    );
+    glyph_grid_to_string(env, next, width, height, &glyphs)
+}
+
+/// Returns true if a live grid comes back to exactly the same state after
+/// `period` generations under Conway's rule. The generations are stepped in
+/// `a` and `b`, each at least `width * height` long, so a caller testing many
+/// grids can allocate them once.
+fn returns_after(
+    env: &Env,
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    period: u32,
+    a: &mut [u8],
+    b: &mut [u8],
+) -> bool {
+    let cells = width * height;
+    if live_count(&grid[..cells]) == 0 {
+        return false;
+    }
+
+    a[..cells].copy_from_slice(&grid[..cells]);
+    let (mut current, mut next) = (a, b);
+    for _ in 0..period {
+        step_grid(
+            env,
+            &current[..],
+            &mut next[..],
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        core::mem::swap(&mut current, &mut next);
+    }
+    current[..cells] == grid[..cells]
+}
+
+/// Counts the live cells in a slice of a grid.
+fn live_count(cells: &[u8]) -> u32 {
+    cells.iter().filter(|&&c| c != b' ').count() as u32
+}
+
+/// Returns the inclusive `(min_x, min_y, max_x, max_y)` box around the live
+/// cells of a grid, or `None` if every cell is dead.
+fn live_bounds(grid: &[u8], width: usize, height: usize) -> Option<(usize, usize, usize, usize)> {
+    let mut bounds: Option<(usize, usize, usize, usize)> = None;
+    for y in 0..height {
+        for x in 0..width {
+            if grid[y * width + x] == b' ' {
+                continue;
+            }
+            bounds = Some(match bounds {
+                None => (x, y, x, y),
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            });
+        }
+    }
+    bounds
+}
+
+/// Writes the `cols` x `rows` region of a grid into `out` in board format and
+/// returns the number of bytes written.
+fn write_region(
+    grid: &[u8],
+    width: usize,
+    cols: Range<usize>,
+    rows: Range<usize>,
+    out: &mut [u8],
+) -> usize {
+    let mut len = 0usize;
+    let row_len = cols.end - cols.start;
+    for y in rows.clone() {
+        if y > rows.start {
+            out[len] = b'\n';
+            len += 1;
+        }
+        let row = y * width;
+        out[len..len + row_len].copy_from_slice(&grid[row + cols.start..row + cols.end]);
+        len += row_len;
+    }
+    len
+}
+
+/// Renders a row-major grid back into the newline-delimited board format.
+fn grid_to_string(env: &Env, grid: &[u8], width: usize, height: usize) -> String {
+    let mut buffer = alloc::vec![0u8; (width + 1) * height];
+    let len = write_region(grid, width, 0..width, 0..height, &mut buffer);
+    String::from_bytes(env, &buffer[..len])
+}
+
+/// Returns the byte length of a grid loaded by `load_glyph_grid` once it is
+/// rendered by `glyph_grid_to_string`.
+fn glyph_grid_len(grid: &[u8], width: usize, height: usize, glyphs: &Glyphs) -> usize {
+    let cells: usize = grid[..width * height]
+        .iter()
+        .map(|&cell| glyphs.expand(cell).1)
+        .sum();
+    cells + height - 1
+}
+
+/// Renders a grid loaded by `load_glyph_grid`, expanding stand-in bytes back
+/// into their characters. Fails with `Error::ResultTooLarge` if the result
+/// would exceed `MAX_BOARD_SIZE` bytes, as newborn multi-byte characters can
+/// make a board outgrow its input.
+fn glyph_grid_to_string(
+    env: &Env,
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    glyphs: &Glyphs,
+) -> String {
+    if glyphs.is_empty() {
+        return grid_to_string(env, grid, width, height);
+    }
+
+    let len = glyph_grid_len(grid, width, height, glyphs);
+    if len > MAX_BOARD_SIZE {
+        panic_with_error!(env, Error::ResultTooLarge);
+    }
+    let mut buffer = alloc::vec::Vec::with_capacity(len);
+    for y in 0..height {
+        if y > 0 {
+            buffer.push(b'\n');
+        }
+        for &cell in &grid[y * width..(y + 1) * width] {
+            let (bytes, n) = glyphs.expand(cell);
+            buffer.extend_from_slice(&bytes[..n]);
+        }
+    }
+    String::from_bytes(env, &buffer)
+}
+
+/// Writes the live region of a grid, trimmed to its bounding box, into `out`
+/// and returns the number of bytes written (zero for a dead grid).
+fn write_trimmed(grid: &[u8], width: usize, height: usize, out: &mut [u8]) -> usize {
+    match live_bounds(grid, width, height) {
+        Some((x0, y0, x1, y1)) => write_region(grid, width, x0..x1 + 1, y0..y1 + 1, out),
+        None => 0,
+    }
+}
+
+/// Writes `value` to persistent storage under `key` and extends its lifetime.
+fn save_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    let storage = env.storage().persistent();
+    storage.set(key, value);
+    storage.extend_ttl(key, BOARD_TTL_THRESHOLD, BOARD_TTL_EXTEND);
+}
+
+/// Saves `board` and its generation count to persistent storage and extends
+/// both entries' lifetimes. Any generation in progress was computed from the
+/// board being replaced, so it is discarded.
+fn save_board(env: &Env, board: &String, generation: u32) {
+    save_persistent(env, &DataKey::Board, board);
+    save_persistent(env, &DataKey::Generation, &generation);
+    env.storage().persistent().remove(&DataKey::Slice);
+}
+
+/// Saves `board` as the stored board's next generation.
+fn store_next_generation(env: &Env, board: &String) {
+    let generation: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Generation)
+        .unwrap_or(0);
+    save_board(env, board, generation.saturating_add(1));
+}
+
+/// Tallies, per colony, the cells born, the cells that died, and the cells
+/// that survived going from `before` to `after`, keyed by the colony's
+/// character. Empty if either board cannot be loaded or their sizes differ.
+fn colony_changes(env: &Env, before: &String, after: &String) -> Map<u32, (u32, u32, u32)> {
+    let mut changes = Map::new(env);
+    let mut old = alloc::vec![0u8; before.len() as usize];
+    let mut new = alloc::vec![0u8; after.len() as usize];
+    let (mut old_glyphs, mut new_glyphs) = (Glyphs::new(), Glyphs::new());
+    let (Ok(old_size), Ok(new_size)) = (
+        load_glyph_grid(before, &mut old, &mut old_glyphs),
+        load_glyph_grid(after, &mut new, &mut new_glyphs),
+    ) else {
+        return changes;
+    };
+    if old_size != new_size {
+        return changes;
+    }
+
+    for i in 0..old_size.0 * old_size.1 {
+        let (colony, change) = match (old[i], new[i]) {
+            (b' ', b' ') => continue,
+            (b' ', c) => (new_glyphs.code_point(c), (1, 0, 0)),
+            (c, b' ') => (old_glyphs.code_point(c), (0, 1, 0)),
+            (_, c) => (new_glyphs.code_point(c), (0, 0, 1)),
+        };
+        let (births, deaths, survivors) = changes.get(colony).unwrap_or((0, 0, 0));
+        changes.set(
+            colony,
+            (births + change.0, deaths + change.1, survivors + change.2),
+        );
+    }
+    changes
+}
+
+#[contract]
+pub struct GameOfLife;
+
+#[contractimpl]
+impl GameOfLife {
+    /// Computes the next generation of Conway's Game of Life.
+    /// Board format: rows separated by newlines, whitespace = dead, any other char = alive.
+    /// Newly born cells inherit the dominant neighbor type; ties are broken randomly.
+    /// A multi-byte UTF-8 character is a single cell of its own type.
+    /// Fails with `Error::RaggedRows` if the rows differ in length, and with
+    /// `Error::ResultTooLarge` if newborn multi-byte characters would push the
+    /// result past `MAX_BOARD_SIZE` bytes.
+    pub fn next_generation(env: Env, board: String) -> String {
+        Self::next_generation_with_rules(env, board, Rule::CONWAY.birth, Rule::CONWAY.survival)
+    }
+
+    /// Computes the next generation under an arbitrary birth/survival rule: bit
+    /// `n` of `birth_mask` (`survive_mask`) means a dead (live) cell with `n`
+    /// live neighbors is born (survives). Bits above 8 are ignored. Fails with
+    /// `Error::RaggedRows` if the rows differ in length.
+    pub fn next_generation_with_rules(
+        env: Env,
+        board: String,
+        birth_mask: u32,
+        survive_mask: u32,
+    ) -> String {
+        let rule = Rule::new(birth_mask, survive_mask);
+        let (mut grid, mut next) = (alloc::vec::Vec::new(), alloc::vec::Vec::new());
+        evolve_glyph_board(&env, board, rule, BoundaryMode::Dead, &mut grid, &mut next)
+    }
+
+    /// Computes the next generation like `next_generation`, but returns it as a
+    /// newline-free `width * height` buffer along with `(width, height)`.
+    /// Empty or oversized boards return an empty buffer and zero dimensions.
+    pub fn next_generation_flat(env: Env, board: String) -> (Bytes, u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (Bytes::new(&env), 0, 0);
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        (
+            Bytes::from_slice(&env, &next[..width * height]),
+            width as u32,
+            height as u32,
+        )
+    }
+
+    /// Computes the next generation of the thermal variant, where cells `0`..`9`
+    /// are heat levels and every other byte is dead. Each cell takes the rounded
+    /// average heat of its live neighbors; results below the threshold die.
+    pub fn next_generation_thermal(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let (_, neighbor_types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+
+                let mut heat_sum = 0u32;
+                let mut sources = 0u32;
+                for &t in neighbor_types[..type_count]
+                    .iter()
+                    .filter(|t| t.is_ascii_digit())
+                {
+                    heat_sum += (t - b'0') as u32;
+                    sources += 1;
+                }
+
+                let heat = (heat_sum + sources / 2).checked_div(sources).unwrap_or(0);
+                next[y * width + x] = if heat >= THERMAL_THRESHOLD {
+                    b'0' + heat as u8
+                } else {
+                    b' '
+                };
+            }
+        }
+
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation like `next_generation`, but only if every live
+    /// cell's type appears in `palette`. Boards containing any other colony are
+    /// returned unchanged to signal the rejection.
+    pub fn next_generation_palette(env: Env, board: String, palette: Bytes) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut allowed = [false; 256];
+        for t in palette.iter() {
+            allowed[t as usize] = true;
+        }
+        if grid[..width * height]
+            .iter()
+            .any(|&c| c != b' ' && !allowed[c as usize])
+        {
+            return board;
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation with a corpse channel: a dying `A`..`Z` cell
+    /// leaves a lowercase corpse of its type, and corpses count as dead. A corpse
+    /// with at least two live neighbors comes back as its former type with
+    /// `revive_chance_percent` probability; otherwise it follows the normal birth
+    /// rule or decays to a space.
+    pub fn next_generation_revive(env: Env, board: String, revive_chance_percent: u32) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+        let cells = width * height;
+
+        // Neighbor scans see corpses as dead cells.
+        let mut live = alloc::vec![0u8; width * height];
+        for (l, &c) in live[..cells].iter_mut().zip(&grid[..cells]) {
+            *l = if c.is_ascii_lowercase() { b' ' } else { c };
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let current_char = grid[y * width + x];
+                let (neighbors, neighbor_types, type_count) =
+                    get_neighbor_info(&live, x as i32, y as i32, width, height, BoundaryMode::Dead);
+
+                next[y * width + x] = if current_char.is_ascii_lowercase()
+                    && neighbors >= 2
+                    && env.prng().gen_range::<u64>(0..100) < revive_chance_percent as u64
+                {
+                    current_char.to_ascii_uppercase()
+                } else if live[y * width + x] != b' ' {
+                    if neighbors == 2 || neighbors == 3 {
+                        current_char
+                    } else if current_char.is_ascii_uppercase() {
+                        current_char.to_ascii_lowercase()
+                    } else {
+                        b' '
+                    }
+                } else if neighbors == 3 {
+                    get_dominant_type(
+                        &env,
+                        &neighbor_types[..type_count],
+                        type_count,
+                        &mut Dominance::random(),
+                    )
+                } else {
+                    b' '
+                };
+            }
+        }
+
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Returns a canonical key for an oscillator: the lexicographically smallest of
+    /// its phases, each trimmed to its live bounding box. If the board does not
+    /// return to itself within `max_period` generations (at most `MAX_STEPS`),
+    /// the trimmed input is returned. A fully dead board yields an empty string.
+    pub fn oscillator_canonical(env: Env, board: String, max_period: u32) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+        let cells = width * height;
+
+        // A trimmed phase is at most every cell plus a newline per row.
+        let mut best = alloc::vec![0u8; (width + 1) * height];
+        let mut best_len = write_trimmed(&grid, width, height, &mut best);
+        let mut candidate = alloc::vec![0u8; (width + 1) * height];
+
+        let mut a = grid[..cells].to_vec();
+        let mut b = alloc::vec![0u8; cells];
+        let (mut current, mut next) = (&mut a, &mut b);
+        let mut is_oscillator = false;
+
+        for _ in 0..max_period.min(MAX_STEPS) {
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+            if current[..cells] == grid[..cells] {
+                is_oscillator = true;
+                break;
+            }
+            let len = write_trimmed(&current[..], width, height, &mut candidate);
+            if candidate[..len] < best[..best_len] {
+                best[..len].copy_from_slice(&candidate[..len]);
+                best_len = len;
+            }
+        }
+
+        if !is_oscillator {
+            best_len = write_trimmed(&grid, width, height, &mut best);
+        }
+        String::from_bytes(&env, &best[..best_len])
+    }
+
+    /// Counts the live cells on the boundary of the live region: those with at
+    /// least one dead or off-board orthogonal neighbor.
+    pub fn perimeter(_env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+
+        let is_dead = |x: i32, y: i32| {
+            x < 0
+                || y < 0
+                || x >= width as i32
+                || y >= height as i32
+                || grid[y as usize * width + x as usize] == b' '
+        };
+
+        let mut count = 0u32;
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                if is_dead(x, y) {
+                    continue;
+                }
+                if is_dead(x - 1, y) || is_dead(x + 1, y) || is_dead(x, y - 1) || is_dead(x, y + 1)
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Returns how many distinct live cell types appear on the board.
+    pub fn distinct_types(_env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+
+        let mut seen = [false; 256];
+        let mut count = 0u32;
+        for &c in &grid[..width * height] {
+            if c != b' ' && !seen[c as usize] {
+                seen[c as usize] = true;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Replaces each live cell's type according to `mapping`, keyed and valued by
+    /// cell byte. Unmapped types and dead cells are left as they are; entries that
+    /// do not map one live cell byte to another are ignored.
+    pub fn remap_types(env: Env, board: String, mapping: Map<u32, u32>) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut table = [0u8; 256];
+        for (i, t) in table.iter_mut().enumerate() {
+            *t = i as u8;
+        }
+        for (from, to) in mapping.iter() {
+            if from > 0xff || to > 0xff {
+                continue;
+            }
+            let (from, to) = (from as u8, to as u8);
+            if from != b' ' && from != b'\n' && !is_blank(to) && to != b'\n' {
+                table[from as usize] = to;
+            }
+        }
+
+        for c in grid[..width * height].iter_mut() {
+            *c = table[*c as usize];
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Returns true if no live cell of type `colony` remains on the board.
+    pub fn is_colony_extinct(_env: Env, board: String, colony: u32) -> bool {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return true;
+        };
+
+        !grid[..width * height]
+            .iter()
+            .any(|&c| c != b' ' && c as u32 == colony)
+    }
+
+    /// Computes the next generation and a trace of `(x, y, old_byte, new_byte)`
+    /// for every cell in row-major order, including unchanged cells. The trace is
+    /// meant for small test boards: boards over `MAX_TRACE_CELLS` (1024) cells
+    /// still evolve but return an empty trace.
+    pub fn trace_step(env: Env, board: String) -> (String, Vec<(u32, u32, u32, u32)>) {
+        let mut trace = Vec::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (board, trace);
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        if width * height <= MAX_TRACE_CELLS {
+            for y in 0..height {
+                for x in 0..width {
+                    let i = y * width + x;
+                    trace.push_back((x as u32, y as u32, grid[i] as u32, next[i] as u32));
+                }
+            }
+        }
+        (grid_to_string(&env, &next, width, height), trace)
+    }
+
+    /// Buckets every cell by its fate in the next generation and returns
+    /// `(survivors, deaths_underpop, deaths_overpop, births)`.
+    pub fn fate_counts(_env: Env, board: String) -> (u32, u32, u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (0, 0, 0, 0);
+        };
+
+        let (mut survivors, mut underpop, mut overpop, mut births) = (0u32, 0u32, 0u32, 0u32);
+        for y in 0..height {
+            for x in 0..width {
+                let (neighbors, _, _) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                if grid[y * width + x] != b' ' {
+                    match neighbors {
+                        0 | 1 => underpop += 1,
+                        2 | 3 => survivors += 1,
+                        _ => overpop += 1,
+                    }
+                } else if neighbors == 3 {
+                    births += 1;
+                }
+            }
+        }
+        (survivors, underpop, overpop, births)
+    }
+
+    /// Computes the next generation under the hybrid-vigor rule: a dead cell with
+    /// exactly 3 neighbors is only born if those neighbors span at least two
+    /// colonies, and the newborn takes the smallest parent byte. Survival follows
+    /// the normal rule.
+    pub fn next_generation_hybrid(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let current_char = grid[y * width + x];
+                let (neighbors, neighbor_types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+
+                next[y * width + x] = if current_char != b' ' {
+                    if neighbors == 2 || neighbors == 3 {
+                        current_char
+                    } else {
+                        b' '
+                    }
+                } else if neighbors == 3 {
+                    let parents = &neighbor_types[..type_count];
+                    let min = parents.iter().copied().min().unwrap_or(b' ');
+                    if parents.iter().any(|&t| t != min) {
+                        min
+                    } else {
+                        b' '
+                    }
+                } else {
+                    b' '
+                };
+            }
+        }
+
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation like `next_generation`, but breaks ties by
+    /// consuming `entropy` one byte per tie (modulo the number of tied types)
+    /// instead of the PRNG. Once `entropy` runs out, ties go to the smallest byte.
+    pub fn next_generation_with_entropy(env: Env, board: String, entropy: Bytes) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        let mut dominance = Dominance {
+            tally: Tally::Count,
+            tie: TieBreak::Entropy {
+                bytes: &entropy,
+                cursor: 0,
+            },
+        };
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut dominance,
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Runs `generations` steps (at most `MAX_STEPS`) alternating two
+    /// outer-totalistic rules: rule A (`birth_a`/`survival_a`) on even
+    /// generations and rule B on odd ones. Bit `n` of a mask means `n` live
+    /// neighbors triggers birth or survival.
+    pub fn step_n_alternating(
+        env: Env,
+        board: String,
+        birth_a: u32,
+        survival_a: u32,
+        birth_b: u32,
+        survival_b: u32,
+        generations: u32,
+    ) -> String {
+        let mut a = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut a) else {
+            return board;
+        };
+
+        let rules = [
+            Rule::new(birth_a, survival_a),
+            Rule::new(birth_b, survival_b),
+        ];
+        let mut b = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut a, &mut b);
+        for generation in 0..generations.min(MAX_STEPS) {
+            let rule = rules[(generation % 2) as usize];
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                rule,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+        }
+
+        grid_to_string(&env, &current[..], width, height)
+    }
+
+    /// Finds the largest all-dead axis-aligned rectangle on the board and returns
+    /// it as `(x, y, width, height)`, with `(x, y)` its top-left cell. Returns
+    /// `(0, 0, 0, 0)` when there are no dead cells.
+    pub fn largest_empty_rect(_env: Env, board: String) -> (u32, u32, u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (0, 0, 0, 0);
+        };
+
+        // Each row is treated as the base of a histogram of dead-cell runs, whose
+        // largest rectangle is found with a monotonic stack of column indices.
+        let mut runs = alloc::vec![0u32; width + 1];
+        let mut stack = alloc::vec![0u32; width + 1];
+        let mut best = (0u32, 0u32, 0u32, 0u32);
+        let mut best_area = 0u32;
+
+        for y in 0..height {
+            for x in 0..width {
+                runs[x] = if grid[y * width + x] == b' ' {
+                    runs[x] + 1
+                } else {
+                    0
+                };
+            }
+
+            let mut depth = 0usize;
+            for x in 0..=width {
+                let run = if x < width { runs[x] } else { 0 };
+                while depth > 0 && runs[stack[depth - 1] as usize] >= run {
+                    let h = runs[stack[depth - 1] as usize];
+                    depth -= 1;
+                    let left = if depth == 0 { 0 } else { stack[depth - 1] + 1 };
+                    let w = x as u32 - left;
+                    if h * w > best_area {
+                        best_area = h * w;
+                        best = (left, y as u32 + 1 - h, w, h);
+                    }
+                }
+                stack[depth] = x as u32;
+                depth += 1;
+            }
+        }
+        best
+    }
+
+    /// Returns each colony's centroid, keyed by cell byte, as rounded `(x, y)`.
+    pub fn colony_centroids(env: Env, board: String) -> Map<u32, (u32, u32)> {
+        let mut centroids = Map::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return centroids;
+        };
+
+        let mut sums = [(0u64, 0u64, 0u64); 256];
+        for y in 0..height {
+            for x in 0..width {
+                let c = grid[y * width + x];
+                if c != b' ' {
+                    let entry = &mut sums[c as usize];
+                    entry.0 += x as u64;
+                    entry.1 += y as u64;
+                    entry.2 += 1;
+                }
+            }
+        }
+
+        for (t, &(sx, sy, n)) in sums.iter().enumerate() {
+            if n > 0 {
+                centroids.set(t as u32, (round_div(sx, n) as u32, round_div(sy, n) as u32));
+            }
+        }
+        centroids
+    }
+
+    /// Steps the board until a cell of colony `a` is adjacent to a cell of
+    /// colony `b` and returns that generation (0 if they already touch). The
+    /// window is `max_generations` (at most `MAX_STEPS`); returns one past it
+    /// if they never meet within it.
+    pub fn first_contact(env: Env, board: String, a: u32, b: u32, max_generations: u32) -> u32 {
+        let max_generations = max_generations.min(MAX_STEPS);
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return max_generations.saturating_add(1);
+        };
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        for generation in 0..=max_generations {
+            if colonies_touch(&current[..], width, height, a, b) {
+                return generation;
+            }
+            if generation < max_generations {
+                step_grid(
+                    &env,
+                    &current[..],
+                    &mut next[..],
+                    width,
+                    height,
+                    Rule::CONWAY,
+                    BoundaryMode::Dead,
+                    &mut Dominance::random(),
+                );
+                core::mem::swap(&mut current, &mut next);
+            }
+        }
+        max_generations.saturating_add(1)
+    }
+
+    /// Returns the byte length of the string `next_generation` would return.
+    /// Boards keep their dimensions, so for single-byte boards this is
+    /// `width * height + height - 1`, found without computing the generation.
+    /// Boards with multi-byte characters are evolved and measured, since each
+    /// newborn's character sets its width; a tie between characters of
+    /// different widths is broken by the PRNG, as in `next_generation`.
+    /// Unusable boards come back as-is, so their length is the input's. Fails
+    /// with `RaggedRows` or `ResultTooLarge` wherever `next_generation` would.
+    pub fn next_generation_output_len(env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let mut glyphs = Glyphs::new();
+        let (width, height) = match load_glyph_grid(&board, &mut grid, &mut glyphs) {
+            Ok(dimensions) => dimensions,
+            Err(Error::RaggedRows) => panic_with_error!(&env, Error::RaggedRows),
+            Err(_) => return board.len(),
+        };
+        if glyphs.is_empty() {
+            return (width * height + height - 1) as u32;
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        let len = glyph_grid_len(&next, width, height, &glyphs);
+        if len > MAX_BOARD_SIZE {
+            panic_with_error!(&env, Error::ResultTooLarge);
+        }
+        len as u32
+    }
+
+    /// Merges two same-sized boards cell by cell. A cell live on only one board
+    /// keeps that cell; a cell live on both with different types goes to one of
+    /// them at random. Fails with the board's error if either board can't be
+    /// loaded, and with `DimensionMismatch` if their dimensions differ.
+    pub fn resolve_combat(env: Env, board_a: String, board_b: String) -> String {
+        let mut grid_a = alloc::vec![0u8; board_a.len() as usize];
+        let (width, height) =
+            load_grid(&board_a, &mut grid_a).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let mut grid_b = alloc::vec![0u8; board_b.len() as usize];
+        let dimensions =
+            load_grid(&board_b, &mut grid_b).unwrap_or_else(|e| panic_with_error!(&env, e));
+        if dimensions != (width, height) {
+            panic_with_error!(&env, Error::DimensionMismatch);
+        }
+
+        for (a, &b) in grid_a[..width * height]
+            .iter_mut()
+            .zip(&grid_b[..width * height])
+        {
+            if *a == b' ' || (b != b' ' && b != *a && env.prng().gen_range::<u64>(0..2) == 1) {
+                *a = b;
+            }
+        }
+        grid_to_string(&env, &grid_a, width, height)
+    }
+
+    /// Returns a `width * height` field where each dead cell holds the number of
+    /// `colony` cells among its eight neighbors (0..=8). Live cells hold 0.
+    pub fn influence_map(env: Env, board: String, colony: u32) -> Bytes {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return Bytes::new(&env);
+        };
+
+        let mut field = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                if grid[y * width + x] != b' ' {
+                    continue;
+                }
+                let (_, neighbor_types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                field[y * width + x] = neighbor_types[..type_count]
+                    .iter()
+                    .filter(|&&t| t as u32 == colony)
+                    .count() as u8;
+            }
+        }
+        Bytes::from_slice(&env, &field[..width * height])
+    }
+
+    /// Steps the board until `target` appears as an exact sub-rectangle and
+    /// returns that generation (0 if it is already present). The window is
+    /// `max_generations` (at most `MAX_STEPS`); returns one past it if the
+    /// pattern does not appear within it.
+    pub fn generations_until_pattern(
+        env: Env,
+        board: String,
+        target: String,
+        max_generations: u32,
+    ) -> u32 {
+        let max_generations = max_generations.min(MAX_STEPS);
+        let mut pattern = alloc::vec![0u8; target.len() as usize];
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let (Ok((pattern_width, pattern_height)), Ok((width, height))) = (
+            load_grid(&target, &mut pattern),
+            load_grid(&board, &mut first),
+        ) else {
+            return max_generations.saturating_add(1);
+        };
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        for generation in 0..=max_generations {
+            if contains_pattern(
+                &current[..],
+                width,
+                height,
+                &pattern,
+                pattern_width,
+                pattern_height,
+            ) {
+                return generation;
+            }
+            if generation < max_generations {
+                step_grid(
+                    &env,
+                    &current[..],
+                    &mut next[..],
+                    width,
+                    height,
+                    Rule::CONWAY,
+                    BoundaryMode::Dead,
+                    &mut Dominance::random(),
+                );
+                core::mem::swap(&mut current, &mut next);
+            }
+        }
+        max_generations.saturating_add(1)
+    }
+
+    /// Returns the byte length of the board's RLE encoding (header included)
+    /// without building it, for estimating storage costs.
+    pub fn rle_length(_env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+
+        let mut len = 0u32;
+        encode_rle(&grid, width, height, &mut |_| len += 1);
+        len
+    }
+
+    /// Computes the next generation, then makes it symmetric by filling each dead
+    /// cell from its mirror image. `axis` 0 mirrors left-right, 1 top-bottom, and
+    /// 2 both; other values apply no symmetry. On collisions the existing cell
+    /// keeps its type.
+    pub fn next_generation_symmetric(env: Env, board: String, axis: u32) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        if axis == 0 || axis == 2 {
+            for y in 0..height {
+                for x in 0..width {
+                    let mirror = next[y * width + (width - 1 - x)];
+                    if next[y * width + x] == b' ' {
+                        next[y * width + x] = mirror;
+                    }
+                }
+            }
+        }
+        if axis == 1 || axis == 2 {
+            for y in 0..height {
+                for x in 0..width {
+                    let mirror = next[(height - 1 - y) * width + x];
+                    if next[y * width + x] == b' ' {
+                        next[y * width + x] = mirror;
+                    }
+                }
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Counts the live cells with fewer than two live neighbors, which will die
+    /// of underpopulation next generation.
+    pub fn count_isolated(_env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+
+        let mut count = 0u32;
+        for y in 0..height {
+            for x in 0..width {
+                if grid[y * width + x] == b' ' {
+                    continue;
+                }
+                let (neighbors, _, _) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                if neighbors < 2 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Builds a patch turning `base` into `edited`: one 5-byte entry per changed
+    /// cell, holding its big-endian `u32` row-major cell index and the new cell
+    /// byte. Fails with the board's error if either board can't be loaded, and
+    /// with `DimensionMismatch` if their dimensions differ.
+    pub fn make_patch(env: Env, base: String, edited: String) -> Bytes {
+        let mut patch = Bytes::new(&env);
+        let mut before = alloc::vec![0u8; base.len() as usize];
+        let mut after = alloc::vec![0u8; edited.len() as usize];
+        let (width, height) =
+            load_grid(&base, &mut before).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let dimensions =
+            load_grid(&edited, &mut after).unwrap_or_else(|e| panic_with_error!(&env, e));
+        if dimensions != (width, height) {
+            panic_with_error!(&env, Error::DimensionMismatch);
+        }
+
+        for i in 0..width * height {
+            if before[i] != after[i] {
+                patch.extend_from_array(&(i as u32).to_be_bytes());
+                patch.push_back(after[i]);
+            }
+        }
+        patch
+    }
+
+    /// Applies a patch from `make_patch` to `base`. Fails with the board's
+    /// error if `base` can't be loaded, and with `BadPatch` if the patch ends
+    /// in an incomplete entry or an entry points outside the board or holds a
+    /// newline.
+    pub fn apply_patch(env: Env, base: String, patch: Bytes) -> String {
+        let mut grid = alloc::vec![0u8; base.len() as usize];
+        let (width, height) =
+            load_grid(&base, &mut grid).unwrap_or_else(|e| panic_with_error!(&env, e));
+        if !patch.len().is_multiple_of(5) {
+            panic_with_error!(&env, Error::BadPatch);
+        }
+
+        let mut entry = [0u8; 5];
+        for offset in (0..patch.len() / 5).map(|i| i * 5) {
+            patch.slice(offset..offset + 5).copy_into_slice(&mut entry);
+            let index = u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]) as usize;
+            if index >= width * height || entry[4] == b'\n' {
+                panic_with_error!(&env, Error::BadPatch);
+            }
+            grid[index] = if is_blank(entry[4]) { b' ' } else { entry[4] };
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Returns how far the centroid of `colony` moves between the current and
+    /// next generation as `(dx, dy)`, rounded to the nearest cell. Returns
+    /// `(0, 0)` if the colony is absent in either generation.
+    pub fn colony_momentum(env: Env, board: String, colony: u32) -> (i32, i32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (0, 0);
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        let (x0, y0, n0) = colony_sums(&grid, width, height, colony);
+        let (x1, y1, n1) = colony_sums(&next, width, height, colony);
+        if n0 == 0 || n1 == 0 {
+            return (0, 0);
+        }
+
+        // Compare the centroids exactly over the common denominator n0 * n1.
+        let shift = |before: i64, after: i64| {
+            let numerator = 2 * (after * n0 - before * n1);
+            let denominator = 2 * n0 * n1;
+            ((numerator + numerator.signum() * denominator / 2) / denominator) as i32
+        };
+        (shift(x0, x1), shift(y0, y1))
+    }
+
+    /// Kills every live cell whose center lies outside the largest circle
+    /// inscribed in the board's rectangle. Dimensions are unchanged.
+    pub fn apply_circular_mask(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        // Work in doubled coordinates so cell centers and the radius are integers.
+        let diameter = width.min(height) as i64;
+        for y in 0..height {
+            for x in 0..width {
+                let dx = 2 * x as i64 + 1 - width as i64;
+                let dy = 2 * y as i64 + 1 - height as i64;
+                if dx * dx + dy * dy > diameter * diameter {
+                    grid[y * width + x] = b' ';
+                }
+            }
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Computes the next generation of Brian's Brain, where `O` is on, `x` is
+    /// dying, and anything else is off. Off cells with exactly two on neighbors
+    /// turn on, on cells start dying, and dying cells turn off. Only on cells
+    /// count as neighbors.
+    pub fn next_generation_brian(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                next[y * width + x] = match grid[y * width + x] {
+                    b'O' => b'x',
+                    b'x' => b' ',
+                    _ => {
+                        let (_, neighbor_types, type_count) = get_neighbor_info(
+                            &grid,
+                            x as i32,
+                            y as i32,
+                            width,
+                            height,
+                            BoundaryMode::Dead,
+                        );
+                        let on = neighbor_types[..type_count]
+                            .iter()
+                            .filter(|&&t| t == b'O')
+                            .count();
+                        if on == 2 {
+                            b'O'
+                        } else {
+                            b' '
+                        }
+                    }
+                };
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Returns the population after each of the next `generations` generations
+    /// (at most `MAX_STEPS`), a cheap signature for matching a pattern's
+    /// dynamics.
+    pub fn fingerprint(env: Env, board: String, generations: u32) -> Vec<u32> {
+        let mut populations = Vec::new(&env);
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return populations;
+        };
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        for _ in 0..generations.min(MAX_STEPS) {
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+            populations.push_back(live_count(&current[..width * height]));
+        }
+        populations
+    }
+
+    /// Moves a Langton's ant one step. On a dead cell the ant turns right, on a
+    /// live cell it turns left; it then flips the cell (dead becomes `O`, live
+    /// becomes dead) and moves forward one cell, wrapping at the edges.
+    /// Directions are 0 = north, 1 = east, 2 = south, 3 = west. Returns the new
+    /// board and the ant's `(x, y, dir)`; an ant off the board changes nothing.
+    pub fn step_ant(
+        env: Env,
+        board: String,
+        ant_x: u32,
+        ant_y: u32,
+        ant_dir: u32,
+    ) -> (String, u32, u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (board, ant_x, ant_y, ant_dir);
+        };
+        let (x, y) = (ant_x as usize, ant_y as usize);
+        if x >= width || y >= height {
+            return (board, ant_x, ant_y, ant_dir);
+        }
+
+        let cell = &mut grid[y * width + x];
+        let dir = if *cell == b' ' {
+            *cell = b'O';
+            (ant_dir + 1) % 4
+        } else {
+            *cell = b' ';
+            (ant_dir + 3) % 4
+        };
+
+        let (x, y) = match dir {
+            0 => (x, (y + height - 1) % height),
+            1 => ((x + 1) % width, y),
+            2 => (x, (y + 1) % height),
+            _ => ((x + width - 1) % width, y),
+        };
+        (
+            grid_to_string(&env, &grid, width, height),
+            x as u32,
+            y as u32,
+            dir,
+        )
+    }
+
+    /// Computes the next generation with `favored`'s neighbor count boosted by
+    /// `bonus` (capped at 8) when choosing a newborn's colony. Survival and death
+    /// still use the real neighbor counts.
+    pub fn next_generation_handicap(env: Env, board: String, favored: u32, bonus: u32) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        let mut dominance = Dominance {
+            tally: Tally::Bonus {
+                colony: favored,
+                bonus,
+            },
+            tie: TieBreak::Random,
+        };
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut dominance,
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Applies one pass of Zhang-Suen thinning: boundary live cells whose removal
+    /// keeps the pattern connected are cleared, approximating the medial axis.
+    /// Surviving cells keep their types; off-board cells count as dead.
+    pub fn skeleton(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut marked = alloc::vec![false; width * height];
+        for sub_pass in 0..2 {
+            for y in 0..height {
+                for x in 0..width {
+                    if grid[y * width + x] == b' ' {
+                        continue;
+                    }
+                    let live = |dx: i32, dy: i32| {
+                        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                        let inside = nx >= 0 && ny >= 0 && nx < width as i32 && ny < height as i32;
+                        inside && grid[ny as usize * width + nx as usize] != b' '
+                    };
+                    // Neighbors clockwise from north: N, NE, E, SE, S, SW, W, NW.
+                    let ring = [
+                        live(0, -1),
+                        live(1, -1),
+                        live(1, 0),
+                        live(1, 1),
+                        live(0, 1),
+                        live(-1, 1),
+                        live(-1, 0),
+                        live(-1, -1),
+                    ];
+                    let count = ring.iter().filter(|&&l| l).count();
+                    let transitions = (0..8).filter(|&i| !ring[i] && ring[(i + 1) % 8]).count();
+                    let (n, e, s, w) = (ring[0], ring[2], ring[4], ring[6]);
+                    let clear_side = if sub_pass == 0 {
+                        !(e && s && (n || w))
+                    } else {
+                        !(n && w && (e || s))
+                    };
+                    marked[y * width + x] =
+                        (2..=6).contains(&count) && transitions == 1 && clear_side;
+                }
+            }
+            for (cell, mark) in grid[..width * height].iter_mut().zip(marked.iter_mut()) {
+                if *mark {
+                    *cell = b' ';
+                    *mark = false;
+                }
+            }
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Returns true if the board dies out completely within `threshold`
+    /// generations (at most `MAX_STEPS`), marking it as a transient spark
+    /// rather than a persistent pattern. An already-dead board is a spark.
+    pub fn is_spark(env: Env, board: String, threshold: u32) -> bool {
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return true;
+        };
+        let cells = width * height;
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        for _ in 0..threshold.min(MAX_STEPS) {
+            if live_count(&current[..cells]) == 0 {
+                return true;
+            }
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+        }
+        live_count(&current[..cells]) == 0
+    }
+
+    /// Computes a diffusion-limited aggregation step: `seed_type` cells are a
+    /// fixed aggregate, and each dead cell touching it sticks (becomes
+    /// `seed_type`) with `DLA_STICK_PERCENT` probability. Every other cell
+    /// follows the normal rules.
+    pub fn next_generation_dla(env: Env, board: String, seed_type: u32) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let is_seed = |c: u8| c != b' ' && c as u32 == seed_type;
+                if is_seed(grid[i]) {
+                    next[i] = grid[i];
+                    continue;
+                }
+                if grid[i] != b' ' {
+                    continue;
+                }
+                let (_, neighbor_types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                if let Some(&seed) = neighbor_types[..type_count].iter().find(|&&t| is_seed(t)) {
+                    if env.prng().gen_range::<u64>(0..100) < DLA_STICK_PERCENT {
+                        next[i] = seed;
+                    }
+                }
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation from an 18-entry outer-totalistic lookup
+    /// table: entry `n` is the next state of a dead cell with `n` live neighbors
+    /// and entry `9 + n` that of a live cell, each 0 (dead) or 1 (alive). An
+    /// invalid table fails with `BadLookupTable`.
+    pub fn next_generation_lut(env: Env, board: String, lut: Bytes) -> String {
+        if lut.len() != 18 || lut.iter().any(|entry| entry > 1) {
+            panic_with_error!(&env, Error::BadLookupTable);
+        }
+        let mut rule = Rule::new(0, 0);
+        for (i, entry) in lut.iter().enumerate() {
+            let bit = (entry as u32) << (i % 9);
+            if i < 9 {
+                rule.birth |= bit;
+            } else {
+                rule.survival |= bit;
+            }
+        }
+
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            rule,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Returns the rounded centroid of the cells that will be born or die next
+    /// generation, which follows the action rather than the mass. Returns the
+    /// board's center if nothing changes.
+    pub fn activity_centroid(env: Env, board: String) -> (u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (0, 0);
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        let (mut sum_x, mut sum_y, mut changed) = (0u64, 0u64, 0u64);
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                if (grid[i] == b' ') != (next[i] == b' ') {
+                    sum_x += x as u64;
+                    sum_y += y as u64;
+                    changed += 1;
+                }
+            }
+        }
+
+        if changed == 0 {
+            return ((width / 2) as u32, (height / 2) as u32);
+        }
+        (
+            round_div(sum_x, changed) as u32,
+            round_div(sum_y, changed) as u32,
+        )
+    }
+
+    /// Computes the next generation under Anneal (B4678/S35678), a twisted
+    /// majority rule whose domains smooth out and coarsen over time.
+    pub fn next_generation_anneal(env: Env, board: String) -> String {
+        evolve_with_rule(&env, board, Rule::ANNEAL, BoundaryMode::Dead)
+    }
+
+    /// Counts the enclosed dead regions: orthogonally connected groups of dead
+    /// cells that cannot reach the board's border without crossing a live cell.
+    pub fn count_holes(_env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+
+        let mut visited = alloc::vec![false; width * height];
+        let mut stack = alloc::vec![0u32; width * height];
+        let mut fill = |start: usize, visited: &mut [bool]| {
+            visited[start] = true;
+            stack[0] = start as u32;
+            let mut depth = 1usize;
+            while depth > 0 {
+                depth -= 1;
+                let i = stack[depth] as usize;
+                let (x, y) = (i % width, i / width);
+                let neighbors = [
+                    (x > 0).then(|| i - 1),
+                    (x + 1 < width).then(|| i + 1),
+                    (y > 0).then(|| i - width),
+                    (y + 1 < height).then(|| i + width),
+                ];
+                for n in neighbors.into_iter().flatten() {
+                    if grid[n] == b' ' && !visited[n] {
+                        visited[n] = true;
+                        stack[depth] = n as u32;
+                        depth += 1;
+                    }
+                }
+            }
+        };
+
+        // Everything reachable from a border dead cell is outside.
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let on_border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                if on_border && grid[i] == b' ' && !visited[i] {
+                    fill(i, &mut visited);
+                }
+            }
+        }
+
+        let mut holes = 0u32;
+        for i in 0..width * height {
+            if grid[i] == b' ' && !visited[i] {
+                fill(i, &mut visited);
+                holes += 1;
+            }
+        }
+        holes
+    }
+
+    /// Searches for a board that loops seamlessly: one that is back in its
+    /// starting state after `period` generations (not necessarily its minimal
+    /// period). The input is tried first, then up to `max_attempts` variants
+    /// with one randomly chosen cell toggled. Returns the input if none loops.
+    /// `period` is at most `MAX_STEPS`, and the attempts are cut short so that
+    /// no more than `MAX_SEARCH_STEPS` generations run in all.
+    pub fn find_loop_seed(env: Env, board: String, period: u32, max_attempts: u32) -> String {
+        let period = period.min(MAX_STEPS);
+        let max_attempts = max_attempts.min(MAX_SEARCH_STEPS / period.max(1) - 1);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+        let cells = width * height;
+        let mut a = alloc::vec![0u8; cells];
+        let mut b = alloc::vec![0u8; cells];
+        if returns_after(&env, &grid, width, height, period, &mut a, &mut b) {
+            return board;
+        }
+
+        for _ in 0..max_attempts {
+            let i = env.prng().gen_range::<u64>(0..cells as u64) as usize;
+            let original = grid[i];
+            grid[i] = if original == b' ' { b'O' } else { b' ' };
+            if returns_after(&env, &grid, width, height, period, &mut a, &mut b) {
+                return grid_to_string(&env, &grid, width, height);
+            }
+            grid[i] = original;
+        }
+        board
+    }
+
+    /// Returns the `(x, y)` of every cell holding a control byte (below 0x20),
+    /// which has no meaning on a board. Newlines separate rows and the blank
+    /// bytes are dead cells, so neither is reported.
+    pub fn find_invalid_cells(env: Env, board: String) -> Vec<(u32, u32)> {
+        let mut invalid = Vec::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return invalid;
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                if grid[y * width + x] < 0x20 {
+                    invalid.push_back((x as u32, y as u32));
+                }
+            }
+        }
+        invalid
+    }
+
+    /// Steps the board until a generation changes at most `max_change` cells,
+    /// or `max_generations` (at most `MAX_STEPS`) have run. Returns the board
+    /// and the number of generations run.
+    pub fn step_until_calm(
+        env: Env,
+        board: String,
+        max_change: u32,
+        max_generations: u32,
+    ) -> (String, u32) {
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return (board, 0);
+        };
+        let cells = width * height;
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        let mut generations = 0u32;
+        while generations < max_generations.min(MAX_STEPS) {
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+            generations += 1;
+
+            let changed = current[..cells]
+                .iter()
+                .zip(&next[..cells])
+                .filter(|(a, b)| a != b)
+                .count();
+            if changed as u32 <= max_change {
+                break;
+            }
+        }
+        (
+            grid_to_string(&env, &current[..], width, height),
+            generations,
+        )
+    }
+
+    /// Returns up to `limit` (at most `MAX_SUCCESSORS`) distinct next
+    /// generations, one per combination of tie-break outcomes among newborn
+    /// cells. A board without ties has exactly one successor; an invalid board
+    /// has none.
+    pub fn enumerate_successors(env: Env, board: String, limit: u32) -> Vec<String> {
+        let limit = limit.min(MAX_SUCCESSORS);
+        let mut successors = Vec::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return successors;
+        };
+
+        // Each tied cell multiplies the successor count by at least two, so
+        // only the first 32 can matter for a u32 limit.
+        let mut ties = [(0usize, [0u8; 8], 0usize); 32];
+        let mut tie_count = 0usize;
+        let mut combinations = 1u64;
+
+        let mut base = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let (neighbors, types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                let alive = grid[i] != b' ';
+                base[i] = if !Rule::CONWAY.next_alive(alive, neighbors) {
+                    b' '
+                } else if alive {
+                    grid[i]
+                } else {
+                    let (winners, winner_count) = leading_types(&types, type_count, &Tally::Count);
+                    if winner_count > 1 && combinations < limit as u64 {
+                        ties[tie_count] = (i, winners, winner_count);
+                        tie_count += 1;
+                        combinations *= winner_count as u64;
+                    }
+                    winners[0]
+                };
+            }
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        let cells = width * height;
+        for n in 0..combinations.min(limit as u64) {
+            next[..cells].copy_from_slice(&base[..cells]);
+            let mut rest = n;
+            for &(i, winners, winner_count) in &ties[..tie_count] {
+                next[i] = winners[(rest % winner_count as u64) as usize];
+                rest /= winner_count as u64;
+            }
+            successors.push_back(grid_to_string(&env, &next, width, height));
+        }
+        successors
+    }
+
+    /// Returns a bit-packed mask of the cells whose live/dead state flips in the
+    /// next generation, ignoring type. Cells are packed row-major, most
+    /// significant bit first; an invalid board yields empty bytes.
+    pub fn next_generation_xor(env: Env, board: String) -> Bytes {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return Bytes::new(&env);
+        };
+        let cells = width * height;
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        let mut mask = alloc::vec![0u8; cells.div_ceil(8)];
+        for i in 0..cells {
+            if (grid[i] == b' ') != (next[i] == b' ') {
+                mask[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        Bytes::from_slice(&env, &mask)
+    }
+
+    /// Advances one generation and returns the new board along with the number
+    /// of `harvest_type` cells alive before the step: the tick's yield.
+    pub fn tick_with_harvest(env: Env, board: String, harvest_type: u32) -> (String, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (board, 0);
+        };
+        let harvested = grid[..width * height]
+            .iter()
+            .filter(|&&c| c != b' ' && c as u32 == harvest_type)
+            .count() as u32;
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        (grid_to_string(&env, &next, width, height), harvested)
+    }
+
+    /// Computes the next generation under Maze (B3/S12345), which grows seeds
+    /// into labyrinths of branching corridors.
+    pub fn next_generation_maze(env: Env, board: String) -> String {
+        evolve_with_rule(&env, board, Rule::MAZE, BoundaryMode::Dead)
+    }
+
+    /// Returns every live cell as a `(type, x, y)` triple, sorted by type, then
+    /// row, then column: a canonical ordering for hashing and comparison.
+    pub fn sorted_live_cells(env: Env, board: String) -> Vec<(u32, u32, u32)> {
+        let mut cells = Vec::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return cells;
+        };
+
+        let mut seen = [false; 256];
+        for &c in grid[..width * height].iter().filter(|&&c| c != b' ') {
+            seen[c as usize] = true;
+        }
+        for cell_type in (0..=255u8).filter(|&t| seen[t as usize]) {
+            for y in 0..height {
+                for x in 0..width {
+                    if grid[y * width + x] == cell_type {
+                        cells.push_back((cell_type as u32, x as u32, y as u32));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    /// Computes the next generation with births suppressed wherever `region`,
+    /// a same-sized mask, has a live cell. Live cells inside the region still
+    /// survive or die normally. Fails with the board's error if either board
+    /// can't be loaded, and with `DimensionMismatch` if the region's dimensions
+    /// differ from the board's.
+    pub fn next_generation_protected(env: Env, board: String, region: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let (width, height) =
+            load_grid(&board, &mut grid).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let mut mask = alloc::vec![0u8; region.len() as usize];
+        let dimensions =
+            load_grid(&region, &mut mask).unwrap_or_else(|e| panic_with_error!(&env, e));
+        if dimensions != (width, height) {
+            panic_with_error!(&env, Error::DimensionMismatch);
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        for i in 0..width * height {
+            if grid[i] == b' ' && mask[i] != b' ' {
+                next[i] = b' ';
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation after a majority filter that clears single
+    /// cells of noise: a cell whose eight neighbors all disagree with it flips
+    /// to match them (a filled hole takes the dominant type) before the step.
+    pub fn next_generation_denoise(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut smooth = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let (neighbors, types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                smooth[i] = match (grid[i], neighbors) {
+                    (b' ', 8) => {
+                        get_dominant_type(&env, &types, type_count, &mut Dominance::random())
+                    }
+                    (_, 0) => b' ',
+                    (cell, _) => cell,
+                };
+            }
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &smooth,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation on a torus: neighbors past one edge are
+    /// read from the opposite edge, so spaceships cross the border instead of
+    /// stalling against it.
+    pub fn next_generation_toroidal(env: Env, board: String) -> String {
+        evolve_with_rule(&env, board, Rule::CONWAY, BoundaryMode::Toroidal)
+    }
+
+    /// Advances the board `steps` generations (at most `MAX_STEPS`) in one call,
+    /// stopping early once every cell is dead. Each generation costs about as
+    /// many CPU instructions as a `next_generation` call, so large boards can
+    /// exhaust the budget well before the cap.
+    pub fn next_generation_n(env: Env, board: String, steps: u32) -> String {
+        if steps == 0 {
+            return board;
+        }
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return board;
+        };
+        let cells = width * height;
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        for _ in 0..steps.min(MAX_STEPS) {
+            if live_count(&current[..cells]) == 0 {
+                break;
+            }
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+        }
+        grid_to_string(&env, &current[..], width, height)
+    }
+
+    /// Returns each colony's bounding box, keyed by cell byte, as inclusive
+    /// `(min_x, min_y, max_x, max_y)`.
+    pub fn colony_bounding_boxes(env: Env, board: String) -> Map<u32, (u32, u32, u32, u32)> {
+        let mut boxes = Map::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return boxes;
+        };
+
+        let mut bounds: [Option<(u32, u32, u32, u32)>; 256] = [None; 256];
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
+                let c = grid[y as usize * width + x as usize];
+                if c != b' ' {
+                    let entry = &mut bounds[c as usize];
+                    *entry = Some(match *entry {
+                        Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                        None => (x, y, x, y),
+                    });
+                }
+            }
+        }
+
+        for (t, bound) in bounds.iter().enumerate() {
+            if let Some(bound) = *bound {
+                boxes.set(t as u32, bound);
+            }
+        }
+        boxes
+    }
+
+    /// Computes the next generation against reflecting walls: edge cells see
+    /// their mirror images past the border, which keeps patterns that reach a
+    /// wall in play instead of starving them. This is
+    /// `next_generation_with_boundary` with `Reflective` walls: multi-byte
+    /// characters and ragged rows are handled as in `next_generation`.
+    pub fn next_generation_billiard(env: Env, board: String) -> String {
+        evolve_with_rule(&env, board, Rule::CONWAY, BoundaryMode::Reflective)
+    }
+
+    /// Stores `board` in persistent storage as the contract's own board,
+    /// replacing any board stored before, and resets the generation count.
+    /// Discards any generation in progress.
+    pub fn init_board(env: Env, board: String) {
+        save_board(&env, &board, 0);
+    }
+
+    /// Advances the stored board one generation, stores it, and returns it.
+    /// Publishes a `("life", "gen")` event whose data is the new generation
+    /// number and a map from each colony's character to its `(births, deaths,
+    /// survivors)`. Discards any generation in progress. Panics if `init_board`
+    /// has not been called.
+    pub fn step(env: Env) -> String {
+        let board = Self::get_board(env.clone());
+        let next = Self::next_generation(env.clone(), board.clone());
+        store_next_generation(&env, &next);
+        env.events().publish(
+            (symbol_short!("life"), symbol_short!("gen")),
+            (
+                Self::get_generation(env.clone()),
+                colony_changes(&env, &board, &next),
+            ),
+        );
+        next
+    }
+
+    /// Returns the stored board. Panics if `init_board` has not been called.
+    pub fn get_board(env: Env) -> String {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Board)
+            .unwrap_or_else(|| panic!("board not initialized; call init_board first"))
+    }
+
+    /// Returns how many generations the stored board has advanced since
+    /// `init_board`, or 0 if there is no stored board.
+    pub fn get_generation(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Generation)
+            .unwrap_or(0)
+    }
+
+    /// Clears the stored board, its generation count, and any generation in
+    /// progress.
+    pub fn reset(env: Env) {
+        env.storage().persistent().remove(&DataKey::Board);
+        env.storage().persistent().remove(&DataKey::Generation);
+        env.storage().persistent().remove(&DataKey::Slice);
+    }
+
+    /// Returns how well supported `colony` is: its cells' average number of
+    /// same-colony neighbors, scaled so 8 maps to 100. A missing colony scores 0.
+    pub fn colony_health(_env: Env, board: String, colony: u32) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+
+        let mut cells = 0u64;
+        let mut support = 0u64;
+        for y in 0..height {
+            for x in 0..width {
+                let c = grid[y * width + x];
+                if c == b' ' || c as u32 != colony {
+                    continue;
+                }
+                let (_, types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                cells += 1;
+                support += types[..type_count].iter().filter(|&&t| t == c).count() as u64;
+            }
+        }
+        if cells == 0 {
+            return 0;
+        }
+        round_div(support * 100, cells * 8) as u32
+    }
+
+    /// Computes the next generation under Life without Death (B3/S012345678):
+    /// cells are born on exactly three neighbors and, once born, never die.
+    pub fn next_generation_ldn(env: Env, board: String) -> String {
+        evolve_with_rule(&env, board, Rule::LIFE_WITHOUT_DEATH, BoundaryMode::Dead)
+    }
+
+    /// Returns the number of live cells, parsing the board as `next_generation`
+    /// does. Empty and unparseable boards count as 0.
+    pub fn count_population(_env: Env, board: String) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        match load_grid(&board, &mut grid) {
+            Ok((width, height)) => live_count(&grid[..width * height]),
+            Err(_) => 0,
+        }
+    }
+
+    /// Computes the next generation under a Golly-style rulestring such as
+    /// `B36/S23`. A malformed rulestring returns the board unchanged.
+    pub fn next_generation_rulestring(env: Env, rule: String, board: String) -> String {
+        let mut text = [0u8; 32];
+        let len = rule.len() as usize;
+        if len > text.len() {
+            return board;
+        }
+        rule.copy_into_slice(&mut text[..len]);
+        match Rule::parse(&text[..len]) {
+            Some(rule) => Self::next_generation_with_rules(env, board, rule.birth, rule.survival),
+            None => board,
+        }
+    }
+
+    /// Scores `board` by its lifespan: the generation at which it dies out, or
+    /// `max_generations` (at most `MAX_STEPS`) if it is still alive then. A
+    /// score that beats the stored record replaces it. Returns the score.
+    pub fn submit_pattern(env: Env, board: String, max_generations: u32) -> u32 {
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return 0;
+        };
+        let cells = width * height;
+
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        let cap = max_generations.min(MAX_STEPS);
+        let mut lifespan = 0u32;
+        while lifespan < cap && live_count(&current[..cells]) > 0 {
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+            lifespan += 1;
+        }
+
+        let (_, record) = Self::get_record(env.clone());
+        if lifespan > record {
+            save_persistent(&env, &DataKey::Record, &(board, lifespan));
+        }
+        lifespan
+    }
+
+    /// Returns the record board from `submit_pattern` and its lifespan, or an
+    /// empty board and 0 before anything has been submitted.
+    pub fn get_record(env: Env) -> (String, u32) {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Record)
+            .unwrap_or_else(|| (String::from_str(&env, ""), 0))
+    }
+
+    /// Returns the number of live cells of each colony, keyed by cell byte.
+    pub fn count_by_type(env: Env, board: String) -> Map<u32, u32> {
+        let mut counts = Map::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return counts;
+        };
+
+        let mut tally = [0u32; 256];
+        for &c in grid[..width * height].iter().filter(|&&c| c != b' ') {
+            tally[c as usize] += 1;
+        }
+        for (t, &n) in tally.iter().enumerate() {
+            if n > 0 {
+                counts.set(t as u32, n);
+            }
+        }
+        counts
+    }
+
+    /// Returns true if the board is a still life: its next generation equals
+    /// it. Any birth changes the board whichever colony wins the tie, so the
+    /// answer never depends on the PRNG. Unparseable boards are not stable.
+    pub fn is_stable(env: Env, board: String) -> bool {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return false;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        grid[..width * height] == next[..width * height]
+    }
+
+    /// Computes the next generation under Seeds (B2/S): no cell survives, and
+    /// dead cells with exactly two live neighbors are born.
+    pub fn next_generation_seeds(env: Env, board: String) -> String {
+        evolve_with_rule(&env, board, Rule::SEEDS, BoundaryMode::Dead)
+    }
+
+    /// Computes the next generation with births and deaths counted over all
+    /// eight neighbors, but with a newborn taking the dominant type of its
+    /// diagonal neighbors, or of its orthogonal ones if no diagonal is live.
+    pub fn next_generation_diag_inherit(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let (x, y) = (x as i32, y as i32);
+                let (neighbors, _, _) =
+                    get_neighbor_info(&grid, x, y, width, height, BoundaryMode::Dead);
+                let alive = grid[i] != b' ';
+                next[i] = if !Rule::CONWAY.next_alive(alive, neighbors) {
+                    b' '
+                } else if alive {
+                    grid[i]
+                } else {
+                    let mut heirs = neighbor_types_at(
+                        &grid,
+                        x,
+                        y,
+                        width,
+                        height,
+                        BoundaryMode::Dead,
+                        &DIAGONAL,
+                    );
+                    if heirs.1 == 0 {
+                        heirs = neighbor_types_at(
+                            &grid,
+                            x,
+                            y,
+                            width,
+                            height,
+                            BoundaryMode::Dead,
+                            &ORTHOGONAL,
+                        );
+                    }
+                    get_dominant_type(&env, &heirs.0, heirs.1, &mut Dominance::random())
+                };
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Steps the board up to `max_period` times (at most `MAX_STEPS`), hashing
+    /// each state, and returns the length of the first cycle found, or 0 if no
+    /// state repeats in time. Hash collisions are possible but vanishingly rare.
+    pub fn find_period(env: Env, board: String, max_period: u32) -> u32 {
+        let mut first = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut first) else {
+            return 0;
+        };
+        let cells = width * height;
+
+        let mut seen = [0u64; MAX_STEPS as usize + 1];
+        seen[0] = fnv1a(&first[..cells]);
+        let mut second = alloc::vec![0u8; width * height];
+        let (mut current, mut next) = (&mut first, &mut second);
+        for generation in 1..=max_period.min(MAX_STEPS) as usize {
+            step_grid(
+                &env,
+                &current[..],
+                &mut next[..],
+                width,
+                height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            core::mem::swap(&mut current, &mut next);
+
+            let hash = fnv1a(&current[..cells]);
+            if let Some(earlier) = seen[..generation].iter().rposition(|&h| h == hash) {
+                return (generation - earlier) as u32;
+            }
+            seen[generation] = hash;
+        }
+        0
+    }
+
+    /// Computes the next generation like `next_generation`, but a tied birth
+    /// goes to the smallest tied cell byte instead of a random one, so every
+    /// generation can be replayed and verified off-chain.
+    pub fn next_generation_deterministic(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::deterministic(),
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Returns the `(x, y)` of every dead cell with both a colony `a` and a
+    /// colony `b` neighbor: the contested ground where births decide the front.
+    pub fn frontline(env: Env, board: String, a: u32, b: u32) -> Vec<(u32, u32)> {
+        let mut cells = Vec::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return cells;
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                if grid[y * width + x] != b' ' {
+                    continue;
+                }
+                let (_, types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                let borders = |colony: u32| types[..type_count].iter().any(|&t| t as u32 == colony);
+                if borders(a) && borders(b) {
+                    cells.push_back((x as u32, y as u32));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Starts computing the stored board's next generation in slices, so a
+    /// board too large for one call can be advanced over several. Discards any
+    /// generation already in progress. Fails with `Error::RaggedRows`, as
+    /// `step` does, if the stored board's rows differ in length.
+    pub fn begin_generation(env: Env) {
+        let source = Self::get_board(env.clone());
+        let mut grid = alloc::vec![0u8; source.len() as usize];
+        let loaded = load_glyph_grid(&source, &mut grid, &mut Glyphs::new());
+        if loaded == Err(Error::RaggedRows) {
+            panic_with_error!(&env, Error::RaggedRows);
+        }
+        let state = SliceState {
+            source,
+            pending: Bytes::new(&env),
+            next_row: 0,
+        };
+        save_persistent(&env, &DataKey::Slice, &state);
+    }
+
+    /// Computes up to `rows` more rows of the generation in progress and
+    /// returns whether every row is done. Panics if no generation is in
+    /// progress.
+    pub fn advance_slice(env: Env, rows: u32) -> bool {
+        let mut state: SliceState = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Slice)
+            .unwrap_or_else(|| panic!("no generation in progress; call begin_generation first"));
+        let mut grid = alloc::vec![0u8; state.source.len() as usize];
+        let Ok((width, height)) = load_glyph_grid(&state.source, &mut grid, &mut Glyphs::new())
+        else {
+            return true;
+        };
+
+        let start = state.next_row as usize;
+        let end = height.min(start.saturating_add(rows as usize));
+        let mut row = alloc::vec![0u8; width];
+        for y in start..end {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = transition(
+                    &env,
+                    &grid,
+                    width,
+                    height,
+                    x,
+                    y,
+                    Rule::CONWAY,
+                    BoundaryMode::Dead,
+                    &mut Dominance::random(),
+                );
+            }
+            state.pending.extend_from_slice(&row);
+        }
+        state.next_row = end as u32;
+        save_persistent(&env, &DataKey::Slice, &state);
+        end >= height
+    }
+
+    /// Replaces the stored board with the finished generation. Panics unless
+    /// `advance_slice` has reported every row done.
+    pub fn commit_generation(env: Env) {
+        let state: SliceState = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Slice)
+            .unwrap_or_else(|| panic!("no generation in progress; call begin_generation first"));
+        let mut grid = alloc::vec![0u8; state.source.len() as usize];
+        let mut glyphs = Glyphs::new();
+        let next = match load_glyph_grid(&state.source, &mut grid, &mut glyphs) {
+            Ok((width, height)) => {
+                if state.pending.len() as usize != width * height {
+                    panic!("generation incomplete; call advance_slice until it returns true");
+                }
+                state.pending.copy_into_slice(&mut grid[..width * height]);
+                glyph_grid_to_string(&env, &grid, width, height, &glyphs)
+            }
+            Err(_) => state.source,
+        };
+        store_next_generation(&env, &next);
+    }
+
+    /// Computes the next generation after reseeding the environment PRNG from
+    /// `seed`, so a given board and seed always produce the same result. The
+    /// reseed lasts for the rest of the call.
+    pub fn next_generation_seeded(env: Env, board: String, seed: u64) -> String {
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&seed.to_be_bytes());
+        env.prng().seed(Bytes::from_array(&env, &bytes));
+        Self::next_generation(env, board)
+    }
+
+    /// Decodes a Life RLE pattern into a board. Runs use `b` for dead cells,
+    /// `o` for `O`, and any other printable byte for its own colony; `$` ends a
+    /// row and `!` ends the pattern. Malformed patterns yield an empty string.
+    pub fn rle_to_board(env: Env, rle: String) -> String {
+        let len = rle.len() as usize;
+        if len > MAX_BOARD_SIZE {
+            return String::from_str(&env, "");
+        }
+        let mut text = alloc::vec![0u8; len];
+        rle.copy_into_slice(&mut text);
+
+        match decode_rle(&text) {
+            Some((grid, width, height)) => grid_to_string(&env, &grid, width, height),
+            None => String::from_str(&env, ""),
+        }
+    }
+
+    /// Encodes a board as a Life RLE pattern with an `x = W, y = H` header, the
+    /// format `rle_to_board` reads. Unparseable boards yield an empty string.
+    /// Colonies are tagged by their letter; a colony whose character is not a
+    /// letter, or is `b` or `o`, would read back as something else and fails
+    /// with `NoRleTag`.
+    pub fn board_to_rle(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return String::from_str(&env, "");
+        };
+        if !grid[..width * height]
+            .iter()
+            .all(|&cell| rle_taggable(cell))
+        {
+            panic_with_error!(&env, Error::NoRleTag);
+        }
+
+        let mut out = alloc::vec::Vec::new();
+        encode_rle(&grid, width, height, &mut |b| out.push(b));
+        String::from_bytes(&env, &out)
+    }
+
+    /// Computes the next generation under Conway's B3/S23 counted over the
+    /// given neighborhood: all eight surrounding cells (`Moore`) or only the
+    /// four orthogonal ones (`VonNeumann`).
+    pub fn next_generation_neighborhood(
+        env: Env,
+        board: String,
+        neighborhood: Neighborhood,
+    ) -> String {
+        let rule = Rule {
+            neighborhood,
+            ..Rule::CONWAY
+        };
+        evolve_with_rule(&env, board, rule, BoundaryMode::Dead)
+    }
+
+    /// Computes the next generation under a range-`radius` totalistic rule:
+    /// neighbors are counted over the square of cells within `radius`, and bit
+    /// `n` of `birth_mask` (`survive_mask`) means a count of `n` births
+    /// (keeps) a cell. Newborns take the dominant type of their eight
+    /// immediate neighbors, or `O` if those are all dead. A radius above
+    /// `MAX_RADIUS` fails with `UnsupportedRadius`.
+    pub fn next_generation_range(
+        env: Env,
+        board: String,
+        radius: u32,
+        birth_mask: u64,
+        survive_mask: u64,
+    ) -> String {
+        if radius > MAX_RADIUS {
+            panic_with_error!(&env, Error::UnsupportedRadius);
+        }
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let (x, y) = (x as i32, y as i32);
+                let count = count_within(
+                    &grid,
+                    x,
+                    y,
+                    width,
+                    height,
+                    BoundaryMode::Dead,
+                    radius as i32,
+                );
+                let alive = grid[i] != b' ';
+                let mask = if alive { survive_mask } else { birth_mask };
+                next[i] = if mask & (1 << count) == 0 {
+                    b' '
+                } else if alive {
+                    grid[i]
+                } else {
+                    let (_, types, type_count) =
+                        get_neighbor_info(&grid, x, y, width, height, BoundaryMode::Dead);
+                    get_dominant_type(&env, &types, type_count, &mut Dominance::random())
+                };
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Overlays `pattern`'s live cells onto `board` with its top-left corner at
+    /// `(x, y)`. Dead pattern cells are transparent, and anything past the
+    /// board's edges is clipped. An unparseable pattern leaves the board as is.
+    pub fn stamp(env: Env, board: String, pattern: String, x: u32, y: u32) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+        let mut cells = alloc::vec![0u8; pattern.len() as usize];
+        let Ok((pattern_width, pattern_height)) = load_grid(&pattern, &mut cells) else {
+            return board;
+        };
+
+        let (x, y) = (x as usize, y as usize);
+        for py in 0..pattern_height.min(height.saturating_sub(y)) {
+            for px in 0..pattern_width.min(width.saturating_sub(x)) {
+                let cell = cells[py * pattern_width + px];
+                if cell != b' ' {
+                    grid[(y + py) * width + x + px] = cell;
+                }
+            }
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Returns the board cropped to the bounding box of its live cells, colony
+    /// types intact. A board with no live cells crops to an empty string.
+    pub fn crop_to_live(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return String::from_str(&env, "");
+        };
+
+        let mut out = alloc::vec![0u8; (width + 1) * height];
+        let len = write_trimmed(&grid, width, height, &mut out);
+        String::from_bytes(&env, &out[..len])
+    }
+
+    /// Computes the next generation with every `wall` cell fixed in place: walls
+    /// never die or get born, and they don't count as neighbors of other cells.
+    /// A `wall` outside the byte range means there are no walls; a blank or
+    /// newline `wall` fails with `BadWall`.
+    pub fn next_generation_with_walls(env: Env, board: String, wall: u32) -> String {
+        if let Ok(byte) = u8::try_from(wall) {
+            if is_blank(byte) || byte == b'\n' || byte == b'\r' {
+                panic_with_error!(&env, Error::BadWall);
+            }
+        }
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let cell = grid[i];
+                if cell as u32 == wall {
+                    next[i] = cell;
+                    continue;
+                }
+
+                let (_, types, type_count) =
+                    get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+                let mut living = [0u8; 8];
+                let mut neighbors = 0usize;
+                for &t in types[..type_count].iter().filter(|&&t| t as u32 != wall) {
+                    living[neighbors] = t;
+                    neighbors += 1;
+                }
+
+                let alive = cell != b' ';
+                next[i] = if !Rule::CONWAY.next_alive(alive, neighbors as u32) {
+                    b' '
+                } else if alive {
+                    cell
+                } else {
+                    get_dominant_type(&env, &living, neighbors, &mut Dominance::random())
+                };
+            }
+        }
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Rotates the board a quarter turn clockwise; width and height swap.
+    pub fn rotate_cw(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut rotated = alloc::vec![0u8; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                rotated[x * height + (height - 1 - y)] = grid[y * width + x];
+            }
+        }
+        grid_to_string(&env, &rotated, height, width)
+    }
+
+    /// Mirrors the board left to right.
+    pub fn flip_horizontal(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        for row in grid[..width * height].chunks_mut(width) {
+            row.reverse();
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Mirrors the board top to bottom.
+    pub fn flip_vertical(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        for y in 0..height / 2 {
+            let (top, bottom) = grid.split_at_mut((height - 1 - y) * width);
+            top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Returns `(x, y, cell)` for every cell whose byte changes in the next
+    /// generation, `cell` being its new byte (a space once it dies).
+    pub fn next_generation_diff(env: Env, board: String) -> Vec<(u32, u32, u32)> {
+        let mut changes = Vec::new(&env);
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return changes;
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                if next[i] != grid[i] {
+                    changes.push_back((x as u32, y as u32, next[i] as u32));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Computes the next generation after padding each edge that has a live
+    /// cell with a ring of dead cells, so patterns can grow past the original
+    /// bounds by one cell per side per call. If the padded board would exceed
+    /// `MAX_BOARD_SIZE`, the board evolves at its current size instead.
+    pub fn next_generation_growing(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let live = |cell: &u8| *cell != b' ';
+        let top = grid[..width].iter().any(live) as usize;
+        let bottom = grid[(height - 1) * width..height * width].iter().any(live) as usize;
+        let left = (0..height).any(|y| live(&grid[y * width])) as usize;
+        let right = (0..height).any(|y| live(&grid[y * width + width - 1])) as usize;
+        let (grown_width, grown_height) = (width + left + right, height + top + bottom);
+
+        let mut next = alloc::vec![0u8; grown_width * grown_height];
+        if grown_width * grown_height + grown_height - 1 <= MAX_BOARD_SIZE {
+            let mut grown = alloc::vec![b' '; grown_width * grown_height];
+            for y in 0..height {
+                let start = (y + top) * grown_width + left;
+                grown[start..start + width].copy_from_slice(&grid[y * width..(y + 1) * width]);
+            }
+            step_grid(
+                &env,
+                &grown,
+                &mut next,
+                grown_width,
+                grown_height,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut Dominance::random(),
+            );
+            return grid_to_string(&env, &next, grown_width, grown_height);
+        }
+
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Returns the number of live neighbors of the cell at `(x, y)`, as the
+    /// generation step counts them. Off-board coordinates return 0.
+    pub fn neighbors_at(_env: Env, board: String, x: u32, y: u32) -> u32 {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return 0;
+        };
+        if x as usize >= width || y as usize >= height {
+            return 0;
+        }
+
+        let (neighbors, _, _) =
+            get_neighbor_info(&grid, x as i32, y as i32, width, height, BoundaryMode::Dead);
+        neighbors
+    }
+
+    /// Returns the board's `(width, height)` in cells, parsed as
+    /// `next_generation` parses it. Empty or unparseable boards return `(0, 0)`.
+    pub fn dimensions(_env: Env, board: String) -> (u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        match load_glyph_grid(&board, &mut grid, &mut Glyphs::new()) {
+            Ok((width, height)) => (width as u32, height as u32),
+            Err(_) => (0, 0),
+        }
+    }
+
+    /// Returns true if `next_generation` would accept the board as is: it fits
+    /// in `MAX_BOARD_SIZE`, its rows are all the same length, and it holds no
+    /// control bytes (DEL included) besides newlines and the blank bytes.
+    /// Never panics.
+    pub fn validate(_env: Env, board: String) -> bool {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        match load_glyph_grid(&board, &mut grid, &mut Glyphs::new()) {
+            Ok((width, height)) => grid[..width * height]
+                .iter()
+                .all(|&c| c >= 0x20 && c != 0x7f),
+            Err(_) => false,
+        }
+    }
+
+    /// Computes the next generation with each neighbor counting for its
+    /// colony's entry in `weights`, keyed by cell byte, when choosing a
+    /// newborn's colony. Colonies missing from `weights` count 1 per neighbor.
+    /// Survival and death still use the real neighbor counts; ties are broken
+    /// randomly.
+    pub fn next_generation_weighted(env: Env, board: String, weights: Map<u32, u32>) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut table = [1u32; 256];
+        for (colony, weight) in weights.iter() {
+            if let Some(entry) = table.get_mut(colony as usize) {
+                *entry = weight;
+            }
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        let mut dominance = Dominance {
+            tally: Tally::Weighted(&table),
+            tie: TieBreak::Random,
+        };
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut dominance,
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Computes the next generation of Conway's Game of Life where cells die
+    /// of old age: a live cell that has already survived `max_age` generations
+    /// (clamped to 255) dies instead of surviving again. The board has no room
+    /// for ages, so they travel beside it: `ages` holds one byte per cell in
+    /// row-major order, without row separators, counting the generations each
+    /// live cell has survived (0 for dead cells). The new ages are returned
+    /// with the new board, ready for the next call. Empty or mismatched `ages`
+    /// start every cell at 0.
+    pub fn next_generation_aging(
+        env: Env,
+        board: String,
+        ages: Bytes,
+        max_age: u32,
+    ) -> (String, Bytes) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return (board, ages);
+        };
+
+        let cells = width * height;
+        let mut age = alloc::vec![0u8; cells];
+        if ages.len() as usize == cells {
+            ages.copy_into_slice(&mut age);
+        }
+
+        let mut next = alloc::vec![0u8; cells];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        let max_age = max_age.min(MAX_AGE) as u8;
+        for i in 0..cells {
+            if next[i] == b' ' || grid[i] == b' ' {
+                age[i] = 0;
+            } else if age[i] >= max_age {
+                next[i] = b' ';
+                age[i] = 0;
+            } else {
+                age[i] += 1;
+            }
+        }
+        (
+            grid_to_string(&env, &next, width, height),
+            Bytes::from_slice(&env, &age),
+        )
+    }
+
+    /// Computes the next generation of Conway's Game of Life, breaking ties
+    /// between a newborn's equally common neighbor colonies in favor of the
+    /// colony with the most cells on the whole board. Colonies just as large
+    /// fall back to the smallest byte, so the result is deterministic.
+    pub fn next_generation_seniority(env: Env, board: String) -> String {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return board;
+        };
+
+        let mut population = [0u32; 256];
+        for &c in &grid[..width * height] {
+            population[c as usize] += 1;
+        }
+
+        let mut next = alloc::vec![0u8; width * height];
+        let mut dominance = Dominance {
+            tally: Tally::Count,
+            tie: TieBreak::Seniority(&population),
+        };
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut dominance,
+        );
+        grid_to_string(&env, &next, width, height)
+    }
+
+    /// Generates a `width` x `height` board where each cell is alive with
+    /// probability `density_pct / 100` (capped at 100) and a live cell takes a
+    /// glyph drawn from `types`, all using the environment PRNG. Yields an
+    /// empty string if the board would be empty or exceed `MAX_BOARD_SIZE`
+    /// bytes, or if `types` is empty or holds a blank byte or newline.
+    pub fn random_board(
+        env: Env,
+        width: u32,
+        height: u32,
+        density_pct: u32,
+        types: Bytes,
+    ) -> String {
+        let (width, height) = (width as usize, height as usize);
+        let cells = width.checked_mul(height);
+        let fits = cells.is_some_and(|n| n > 0 && n + height - 1 <= MAX_BOARD_SIZE);
+        let usable = types.iter().all(|t| t != b'\n' && !is_blank(t));
+        if !fits || types.is_empty() || !usable {
+            return String::from_str(&env, "");
+        }
+
+        let mut grid = alloc::vec![b' '; width * height];
+        for cell in grid.iter_mut() {
+            if env.prng().gen_range::<u64>(0..100) < density_pct.min(100) as u64 {
+                let pick = env.prng().gen_range::<u64>(0..types.len() as u64);
+                *cell = types.get_unchecked(pick as u32);
+            }
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Returns a `width` x `height` board with the named pattern centered in
+    /// it. Known names are `glider`, `lwss`, `blinker`, `block`, `beacon`,
+    /// `toad`, and `glider_gun`. Yields an empty string for an unknown name, a
+    /// board too small to hold the pattern, or one over `MAX_BOARD_SIZE` bytes.
+    pub fn spawn_pattern(env: Env, name: String, width: u32, height: u32) -> String {
+        let empty = String::from_str(&env, "");
+        let mut text = [0u8; 16];
+        let Some(text) = text.get_mut(..name.len() as usize) else {
+            return empty;
+        };
+        name.copy_into_slice(text);
+        let Some(pattern) = patterns::find(text) else {
+            return empty;
+        };
+        let mut cells = pattern.to_vec();
+        let Ok((pattern_width, pattern_height)) = parse_grid(&mut cells, pattern.len()) else {
+            return empty;
+        };
+
+        let (width, height) = (width as usize, height as usize);
+        let fits = width
+            .checked_mul(height)
+            .is_some_and(|n| n + height - 1 <= MAX_BOARD_SIZE);
+        if !fits || pattern_width > width || pattern_height > height {
+            return empty;
+        }
+
+        let mut grid = alloc::vec![b' '; width * height];
+        let (x0, y0) = ((width - pattern_width) / 2, (height - pattern_height) / 2);
+        for y in 0..pattern_height {
+            let row = (y0 + y) * width + x0;
+            grid[row..row + pattern_width]
+                .copy_from_slice(&cells[y * pattern_width..(y + 1) * pattern_width]);
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Packs a board into one bit per cell: its width and height as big-endian
+    /// `u32`s, then the cells in row-major order, most significant bit first,
+    /// with 1 for live. Colony types are lost, so this only round-trips boards
+    /// with a single colony. Unparseable boards yield empty bytes.
+    pub fn board_to_bitmap(env: Env, board: String) -> Bytes {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut grid) else {
+            return Bytes::new(&env);
+        };
+
+        let mut bits = alloc::vec![0u8; (width * height).div_ceil(8)];
+        for (i, &c) in grid[..width * height].iter().enumerate() {
+            if c != b' ' {
+                bits[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        let mut bitmap = Bytes::from_array(&env, &(width as u32).to_be_bytes());
+        bitmap.extend_from_array(&(height as u32).to_be_bytes());
+        bitmap.extend_from_slice(&bits);
+        bitmap
+    }
+
+    /// Unpacks a `board_to_bitmap` bitmap into a board whose live cells are
+    /// `live_char`. Yields an empty string if the bitmap is malformed or too
+    /// large, or if `live_char` is not a byte that reads as a live cell.
+    pub fn bitmap_to_board(env: Env, bitmap: Bytes, live_char: u32) -> String {
+        let empty = String::from_str(&env, "");
+        let live = match u8::try_from(live_char) {
+            Ok(c) if c != b'\n' && !is_blank(c) => c,
+            _ => return empty,
+        };
+        if bitmap.len() < 8 {
+            return empty;
+        }
+        let mut header = [0u8; 8];
+        bitmap.slice(..8).copy_into_slice(&mut header);
+        let width = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let height = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let Some(cells) = width.checked_mul(height) else {
+            return empty;
+        };
+        let fits = cells > 0 && cells + height - 1 <= MAX_BOARD_SIZE;
+        if !fits || bitmap.len() as usize != 8 + cells.div_ceil(8) {
+            return empty;
+        }
+
+        let mut bits = alloc::vec![0u8; cells.div_ceil(8)];
+        bitmap.slice(8..).copy_into_slice(&mut bits);
+        let mut grid = alloc::vec![b' '; cells];
+        for (i, cell) in grid.iter_mut().enumerate() {
+            if bits[i / 8] & (0x80 >> (i % 8)) != 0 {
+                *cell = live;
+            }
+        }
+        grid_to_string(&env, &grid, width, height)
+    }
+
+    /// Computes the next generation of each board in `boards`, exactly as
+    /// `next_generation` would, and returns the results in order. Each board
+    /// is held to the size limit on its own; one unusable board comes back
+    /// unchanged without affecting the rest. A board that makes
+    /// `next_generation` fail, with `RaggedRows` or `ResultTooLarge`, fails
+    /// the whole batch with that error.
+    pub fn next_generation_batch(env: Env, boards: Vec<String>) -> Vec<String> {
+        let (mut grid, mut next) = (alloc::vec::Vec::new(), alloc::vec::Vec::new());
+        let mut results = Vec::new(&env);
+        for board in boards.iter() {
+            results.push_back(evolve_glyph_board(
+                &env,
+                board,
+                Rule::CONWAY,
+                BoundaryMode::Dead,
+                &mut grid,
+                &mut next,
+            ));
+        }
+        results
+    }
+
+    /// Computes the next generation like `next_generation`, with off-board
+    /// neighbors resolved per `boundary`: `Dead` reads them as dead cells,
+    /// `Toroidal` wraps around to the opposite edge, and `Reflective` mirrors
+    /// back onto the board, so `x = -1` reads `x = 0`.
+    pub fn next_generation_with_boundary(
+        env: Env,
+        board: String,
+        boundary: BoundaryMode,
+    ) -> String {
+        let (mut grid, mut next) = (alloc::vec::Vec::new(), alloc::vec::Vec::new());
+        evolve_glyph_board(&env, board, Rule::CONWAY, boundary, &mut grid, &mut next)
+    }
+
+    /// Computes the next generation like `next_generation` and returns it with
+    /// the number of cells born, the number that died, and the number that
+    /// survived. Unusable boards come back unchanged with all counts at 0.
+    pub fn next_generation_stats(env: Env, board: String) -> (String, u32, u32, u32) {
+        let mut grid = alloc::vec![0u8; board.len() as usize];
+        let mut glyphs = Glyphs::new();
+        let (width, height) = match load_glyph_grid(&board, &mut grid, &mut glyphs) {
+            Ok(dimensions) => dimensions,
+            Err(Error::RaggedRows) => panic_with_error!(&env, Error::RaggedRows),
+            Err(_) => return (board, 0, 0, 0),
+        };
+
+        let mut next = alloc::vec![0u8; width * height];
+        step_grid(
+            &env,
+            &grid,
+            &mut next,
+            width,
+            height,
+            Rule::CONWAY,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        );
+
+        let (mut births, mut deaths, mut survivors) = (0u32, 0u32, 0u32);
+        for (&old, &new) in grid.iter().zip(next.iter()) {
+            match (old != b' ', new != b' ') {
+                (false, true) => births += 1,
+                (true, false) => deaths += 1,
+                (true, true) => survivors += 1,
+                (false, false) => {}
+            }
+        }
+        let result = glyph_grid_to_string(&env, &next, width, height, &glyphs);
+        (result, births, deaths, survivors)
+    }
+
+    /// Computes the next generation like `next_generation` and returns it with
+    /// a flag that is true when it has no live cells left.
+    pub fn next_generation_checked(env: Env, board: String) -> (String, bool) {
+        let next = Self::next_generation(env, board);
+        let mut bytes = alloc::vec![0u8; next.len() as usize];
+        next.copy_into_slice(&mut bytes);
+        let extinct = bytes.iter().all(|&b| b == b'\n' || is_blank(b));
+        (next, extinct)
+    }
+
+    /// Advances the board `steps` generations (at most `MAX_STEPS`) and returns
+    /// its live-cell count after each one. Once every cell is dead the rest of
+    /// the series is 0 without further stepping. Unusable boards yield an
+    /// empty series.
+    pub fn population_series(env: Env, board: String, steps: u32) -> Vec<u32> {
+        let mut series = Vec::new(&env);
+        let mut current = alloc::vec![0u8; board.len() as usize];
+        let Ok((width, height)) = load_grid(&board, &mut current) else {
+            return series;
+        };
+        let cells = width * height;
+
+        let mut next = alloc::vec![0u8; cells];
+        let mut population = live_count(&current[..cells]);
+        for _ in 0..steps.min(MAX_STEPS) {
+            if population > 0 {
+                step_grid(
+                    &env,
+                    &current,
+                    &mut next,
+                    width,
+                    height,
+                    Rule::CONWAY,
+                    BoundaryMode::Dead,
+                    &mut Dominance::random(),
+                );
+                core::mem::swap(&mut current, &mut next);
+                population = live_count(&current[..cells]);
+            }
+            series.push_back(population);
+        }
+        series
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::Env;
+
+    fn setup() -> (Env, GameOfLifeClient<'static>) {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, GameOfLife);
+        let client = GameOfLifeClient::new(&env, &contract_id);
+        (env, client)
+    }
+
+    #[test]
+    fn test_empty_board() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n     \n     ");
+        assert_eq!(client.next_generation(&board), board);
+    }
+
+    #[test]
+    fn test_block_still_life() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.next_generation(&board), board);
+    }
+
+    #[test]
+    fn test_blinker_oscillator() {
+        let (env, client) = setup();
+        let horizontal = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let vertical = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+
+        assert_eq!(client.next_generation(&horizontal), vertical);
+        assert_eq!(client.next_generation(&vertical), horizontal);
+    }
+
+    #[test]
+    fn test_single_cell_dies() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "   \n O \n   ");
+        let expected = String::from_str(&env, "   \n   \n   ");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_overcrowding() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "OOO\nOOO\nOOO");
+        let expected = String::from_str(&env, "O O\n   \nO O");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_birth() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "    \n O  \n OO \n    ");
+        let expected = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_dominant_type_clear_winner() {
+        let (env, client) = setup();
+        // Two X neighbors vs one O neighbor - new cell should be X
+        let board = String::from_str(&env, "   \n X \nX O\n   ");
+        let expected = String::from_str(&env, "   \n X \n X \n   ");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_mixed_types_block_survives() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "    \n XO \n OX \n    ");
+        assert_eq!(client.next_generation(&board), board);
+    }
+
+    #[test]
+    fn test_same_type_blinker() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n     \n XXX \n     \n     ");
+        let expected = String::from_str(&env, "     \n  X  \n  X  \n  X  \n     ");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_birth_inherits_neighbor_type() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n     \n YYY \n     \n     ");
+        let expected = String::from_str(&env, "     \n  Y  \n  Y  \n  Y  \n     ");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_next_generation_flat() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n     \n OOO \n     ");
+        let (flat, width, height) = client.next_generation_flat(&board);
+
+        assert_eq!((width, height), (5, 4));
+        assert_eq!(flat.len(), width * height);
+        assert_eq!(flat, Bytes::from_slice(&env, b"       O    O    O  "));
+    }
+
+    #[test]
+    fn test_thermal_heat_spreads() {
+        let (env, client) = setup();
+        // The hot 9 warms its cooler neighbor and the empty cells around them,
+        // while cells that only see the 1 fall below the threshold.
+        let board = String::from_str(&env, "   \n91 \n   ");
+        let expected = String::from_str(&env, "55 \n 9 \n55 ");
+        assert_eq!(client.next_generation_thermal(&board), expected);
+    }
+
+    #[test]
+    fn test_oscillator_canonical_blinker_phases() {
+        let (env, client) = setup();
+        let horizontal = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let vertical = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        let expected = String::from_str(&env, "O\nO\nO");
+
+        assert_eq!(client.oscillator_canonical(&horizontal, &4), expected);
+        assert_eq!(client.oscillator_canonical(&vertical, &4), expected);
+
+        // A glider never returns to itself, so the search runs to the cap.
+        let glider = String::from_str(&env, "     \n  O  \n   O \n OOO \n     ");
+        assert_eq!(
+            client.oscillator_canonical(&glider, &u32::MAX),
+            String::from_str(&env, " O \n  O\nOOO")
+        );
+    }
+
+    #[test]
+    fn test_palette_rejects_foreign_colony() {
+        let (env, client) = setup();
+        let palette = Bytes::from_slice(&env, b"XO");
+
+        let foreign = String::from_str(&env, "    \n XO \n OZ \n    ");
+        assert_eq!(client.next_generation_palette(&foreign, &palette), foreign);
+
+        let horizontal = String::from_str(&env, "     \n     \n XOX \n     \n     ");
+        let evolved = client.next_generation_palette(&horizontal, &palette);
+        let vertical = String::from_str(&env, "     \n  X  \n  O  \n  X  \n     ");
+        assert_eq!(evolved, vertical);
+    }
+
+    #[test]
+    fn test_revive_corpse() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n OoO \n     ");
+
+        let revived = String::from_str(&env, "     \n oOo \n     ");
+        assert_eq!(client.next_generation_revive(&board, &100), revived);
+
+        let decayed = String::from_str(&env, "     \n o o \n     ");
+        assert_eq!(client.next_generation_revive(&board, &0), decayed);
+    }
+
+    #[test]
+    fn test_perimeter() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.perimeter(&block), 4);
+
+        // The center of a solid 3x3 has no dead orthogonal neighbor.
+        let square = String::from_str(&env, "OOO\nOOO\nOOO");
+        assert_eq!(client.perimeter(&square), 8);
+    }
+
+    #[test]
+    fn test_tabs_are_dead() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "\t\t\t\t\n\tOO\t\n\tOO\t\n\t\t\t\t");
+        let expected = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.next_generation(&board), expected);
+    }
+
+    #[test]
+    fn test_distinct_types() {
+        let (env, client) = setup();
+        let mixed = String::from_str(&env, "    \n XO \n OX \n    ");
+        assert_eq!(client.distinct_types(&mixed), 2);
+
+        let single = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.distinct_types(&single), 1);
+    }
+
+    #[test]
+    fn test_remap_types() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "    \n XO \n OX \n  Z ");
+        let mut mapping = Map::new(&env);
+        mapping.set(b'X' as u32, b'A' as u32);
+        mapping.set(b'O' as u32, b'B' as u32);
+
+        let expected = String::from_str(&env, "    \n AB \n BA \n  Z ");
+        assert_eq!(client.remap_types(&board, &mapping), expected);
+    }
+
+    #[test]
+    fn test_is_colony_extinct() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "    \n XO \n OX \n    ");
+        assert!(!client.is_colony_extinct(&board, &(b'O' as u32)));
+
+        let without_o = String::from_str(&env, "    \n X  \n  X \n    ");
+        assert!(client.is_colony_extinct(&without_o, &(b'O' as u32)));
+        assert!(!client.is_colony_extinct(&without_o, &(b'X' as u32)));
+    }
+
+    #[test]
+    fn test_trace_step() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "   \nOOO\n   ");
+        let (next, trace) = client.trace_step(&board);
+        assert_eq!(next, String::from_str(&env, " O \n O \n O "));
+
+        let (o, sp) = (b'O' as u32, b' ' as u32);
+        let expected = Vec::from_array(
+            &env,
+            [
+                (0, 0, sp, sp),
+                (1, 0, sp, o),
+                (2, 0, sp, sp),
+                (0, 1, o, sp),
+                (1, 1, o, o),
+                (2, 1, o, sp),
+                (0, 2, sp, sp),
+                (1, 2, sp, o),
+                (2, 2, sp, sp),
+            ],
+        );
+        assert_eq!(trace, expected);
+    }
+
+    #[test]
+    fn test_fate_counts() {
+        let (env, client) = setup();
+        // Corners have 3 neighbors and survive; the edges and center overcrowd.
+        let board = String::from_str(&env, "OOO\nOOO\nOOO");
+        assert_eq!(client.fate_counts(&board), (4, 0, 5, 0));
+
+        let blinker = String::from_str(&env, "   \nOOO\n   ");
+        assert_eq!(client.fate_counts(&blinker), (1, 2, 0, 2));
+    }
+
+    #[test]
+    fn test_hybrid_requires_two_colonies() {
+        let (env, client) = setup();
+        let mono = String::from_str(&env, "     \n     \n XXX \n     \n     ");
+        let expected = String::from_str(&env, "     \n     \n  X  \n     \n     ");
+        assert_eq!(client.next_generation_hybrid(&mono), expected);
+
+        let mixed = String::from_str(&env, "     \n     \n XOX \n     \n     ");
+        let expected = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        assert_eq!(client.next_generation_hybrid(&mixed), expected);
+    }
+
+    #[test]
+    fn test_entropy_tie_break() {
+        let (env, client) = setup();
+        // Both birth cells see one X, one O and one Z: a three-way tie each.
+        let board = String::from_str(&env, "     \n     \n XOZ \n     \n     ");
+        let zeros = Bytes::from_slice(&env, &[0, 0]);
+        let other = Bytes::from_slice(&env, &[1, 2]);
+
+        let first = client.next_generation_with_entropy(&board, &zeros);
+        assert_eq!(first, client.next_generation_with_entropy(&board, &zeros));
+        assert_eq!(
+            first,
+            String::from_str(&env, "     \n  X  \n  O  \n  X  \n     ")
+        );
+
+        let second = client.next_generation_with_entropy(&board, &other);
+        assert_ne!(first, second);
+        assert_eq!(
+            second,
+            String::from_str(&env, "     \n  O  \n  O  \n  Z  \n     ")
+        );
+
+        // Without entropy the smallest tied byte wins.
+        let fallback = client.next_generation_with_entropy(&board, &Bytes::new(&env));
+        assert_eq!(
+            fallback,
+            String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ")
+        );
+    }
+
+    #[test]
+    fn test_step_n_alternating() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let (life_b, life_s) = (1 << 3, (1 << 2) | (1 << 3));
+        let (maze_b, maze_s) = (1 << 3, 0b11_1110);
+
+        let life_only = client.step_n_alternating(&board, &life_b, &life_s, &life_b, &life_s, &3);
+        let maze_only = client.step_n_alternating(&board, &maze_b, &maze_s, &maze_b, &maze_s, &3);
+        let alternating = client.step_n_alternating(&board, &life_b, &life_s, &maze_b, &maze_s, &3);
+
+        assert_eq!(
+            life_only,
+            String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ")
+        );
+        assert_eq!(
+            maze_only,
+            String::from_str(&env, "  O  \n OOO \nOO OO\n OOO \n  O  ")
+        );
+        assert_eq!(
+            alternating,
+            String::from_str(&env, "     \n OOO \n O O \n OOO \n     ")
+        );
+
+        // The run stops at `MAX_STEPS`, an even count that restores the blinker.
+        let capped =
+            client.step_n_alternating(&board, &life_b, &life_s, &life_b, &life_s, &u32::MAX);
+        assert_eq!(capped, board);
+    }
+
+    #[test]
+    fn test_largest_empty_rect() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "O O   \n O    \nO O   \n      ");
+        assert_eq!(client.largest_empty_rect(&board), (3, 0, 3, 4));
+
+        let full = String::from_str(&env, "OO\nOO");
+        assert_eq!(client.largest_empty_rect(&full), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_colony_centroids() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "XX     \nXX     \n       \n     OO\n     OO");
+        let centroids = client.colony_centroids(&board);
+
+        assert_eq!(centroids.len(), 2);
+        assert_eq!(centroids.get(b'X' as u32), Some((1, 1)));
+        assert_eq!(centroids.get(b'O' as u32), Some((6, 4)));
+    }
+
+    #[test]
+    fn test_first_contact() {
+        let (env, client) = setup();
+        // An X glider drifts south-east toward an O block.
+        let board = String::from_str(
+            &env,
+            " X        \n  X       \nXXX       \n          \n          \n          \n      OO  \n      OO  \n          ",
+        );
+        let (x, o) = (b'X' as u32, b'O' as u32);
+        assert_eq!(client.first_contact(&board, &x, &o, &20), 11);
+        assert_eq!(client.first_contact(&board, &x, &o, &5), 6);
+
+        let apart = String::from_str(&env, "XX    \nXX    \n      \n      \n    OO\n    OO");
+        assert_eq!(
+            client.first_contact(&apart, &x, &o, &u32::MAX),
+            MAX_STEPS + 1
+        );
+    }
+
+    #[test]
+    fn test_next_generation_output_len() {
+        let (env, client) = setup();
+        // A trailing newline is not reproduced in the output.
+        let board = String::from_str(&env, "     \n     \n OOO \n     \n     \n");
+        let next = client.next_generation(&board);
+        assert_eq!(client.next_generation_output_len(&board), next.len());
+        assert_eq!(next.len(), 29);
+
+        // The newborn completing the block is a two-byte character.
+        let tromino = String::from_str(&env, "éé\né ");
+        let next = client.next_generation(&tromino);
+        assert_eq!(next, String::from_str(&env, "éé\néé"));
+        assert_eq!(client.next_generation_output_len(&tromino), next.len());
+
+        let ragged = String::from_str(&env, "OOO\nOO\nOOOO");
+        assert_eq!(
+            client.try_next_generation_output_len(&ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+    }
+
+    #[test]
+    fn test_resolve_combat() {
+        let (env, client) = setup();
+        let board_a = String::from_str(&env, "XX \n   ");
+        let board_b = String::from_str(&env, " OO\n  O");
+        let merged = client.resolve_combat(&board_a, &board_b);
+
+        let mut cells = [0u8; 7];
+        merged.copy_into_slice(&mut cells);
+        assert_eq!(cells[0], b'X');
+        assert!(cells[1] == b'X' || cells[1] == b'O');
+        assert_eq!(&cells[2..], b"O\n  O");
+
+        let mismatched = String::from_str(&env, "OO\nOO");
+        assert_eq!(
+            client.try_resolve_combat(&board_a, &mismatched),
+            Err(Ok(Error::DimensionMismatch.into()))
+        );
+    }
+
+    #[test]
+    fn test_influence_map() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "X X\n X \nO  ");
+        let field = client.influence_map(&board, &(b'X' as u32));
+
+        assert_eq!(field.len(), 9);
+        // The dead cell between the three X cells feels all of them.
+        assert_eq!(field.get(1), Some(3));
+        assert_eq!(field, Bytes::from_slice(&env, &[0, 3, 0, 2, 0, 2, 0, 1, 1]));
+    }
+
+    #[test]
+    fn test_generations_until_pattern() {
+        let (env, client) = setup();
+        let seed = String::from_str(&env, "    \n OO \n O  \n    ");
+        let block = String::from_str(&env, "OO\nOO");
+        assert_eq!(client.generations_until_pattern(&seed, &block, &5), 1);
+
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        assert_eq!(client.generations_until_pattern(&blinker, &block, &5), 6);
+        assert_eq!(
+            client.generations_until_pattern(&blinker, &block, &u32::MAX),
+            MAX_STEPS + 1
+        );
+    }
+
+    #[test]
+    fn test_rle_length_sparse_board() {
+        let (env, client) = setup();
+        let mut raw = [b' '; 209];
+        for y in 1..10 {
+            raw[y * 21 - 1] = b'\n';
+        }
+        raw[5 * 21 + 10] = b'O';
+        let board = String::from_bytes(&env, &raw);
+
+        // "x = 20, y = 10\n" followed by "5$10bo!".
+        assert_eq!(client.rle_length(&board), 22);
+        assert!(client.rle_length(&board) * 4 < board.len());
+    }
+
+    #[test]
+    fn test_next_generation_symmetric() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \nOO   \nOO   \n     ");
+
+        let mirrored = String::from_str(&env, "     \nOO OO\nOO OO\n     ");
+        assert_eq!(client.next_generation_symmetric(&board, &0), mirrored);
+
+        let top = String::from_str(&env, "OO\nOO\n  \n  \n  ");
+        let expected = String::from_str(&env, "OO\nOO\n  \nOO\nOO");
+        assert_eq!(client.next_generation_symmetric(&top, &1), expected);
+    }
+
+    #[test]
+    fn test_count_isolated() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "O     \n      \n   OO \n   OO \n      ");
+        assert_eq!(client.count_isolated(&board), 1);
+    }
+
+    #[test]
+    fn test_patch_round_trip() {
+        let (env, client) = setup();
+        let base = String::from_str(&env, "    \n OO \n OO \n    ");
+        let edited = String::from_str(&env, "X   \n OO \n O  \n   Z");
+
+        let patch = client.make_patch(&base, &edited);
+        assert_eq!(patch.len(), 15);
+        assert_eq!(client.apply_patch(&base, &patch), edited);
+
+        let wide = String::from_str(&env, "     \n OO  \n OO  \n     ");
+        assert_eq!(
+            client.try_make_patch(&base, &wide),
+            Err(Ok(Error::DimensionMismatch.into()))
+        );
+        let ragged = String::from_str(&env, "    \n OO\n OO \n    ");
+        assert_eq!(
+            client.try_make_patch(&base, &ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+
+        for bad in [
+            &[0u8, 0, 0, 0][..],
+            &[0, 0, 0, 16, b'X'][..],
+            &[0, 0, 0, 0, b'\n'][..],
+        ] {
+            assert_eq!(
+                client.try_apply_patch(&base, &Bytes::from_slice(&env, bad)),
+                Err(Ok(Error::BadPatch.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_colony_momentum() {
+        let (env, client) = setup();
+        // The lone eastern X dies, pulling the colony's mass west onto its block.
+        let board = String::from_str(&env, "XX        X\nXX         \n           ");
+        assert_eq!(client.colony_momentum(&board, &(b'X' as u32)), (-2, 0));
+
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.colony_momentum(&block, &(b'O' as u32)), (0, 0));
+    }
+
+    #[test]
+    fn test_apply_circular_mask() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "OOOOO\nOOOOO\nOOOOO\nOOOOO\nOOOOO");
+        let expected = String::from_str(&env, " OOO \nOOOOO\nOOOOO\nOOOOO\n OOO ");
+        assert_eq!(client.apply_circular_mask(&board), expected);
+    }
+
+    #[test]
+    fn test_brian_progression() {
+        let (env, client) = setup();
+        let lone = String::from_str(&env, "   \n O \n   ");
+        let dying = client.next_generation_brian(&lone);
+        assert_eq!(dying, String::from_str(&env, "   \n x \n   "));
+        assert_eq!(
+            client.next_generation_brian(&dying),
+            String::from_str(&env, "   \n   \n   ")
+        );
+
+        let pair = String::from_str(&env, "   \nO O\n   ");
+        let expected = String::from_str(&env, " O \nxOx\n O ");
+        assert_eq!(client.next_generation_brian(&pair), expected);
+    }
+
+    #[test]
+    fn test_fingerprint_blinker() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let expected = Vec::from_array(&env, [3u32, 3, 3, 3]);
+        assert_eq!(client.fingerprint(&blinker, &4), expected);
+        assert_eq!(client.fingerprint(&blinker, &u32::MAX).len(), MAX_STEPS);
+    }
+
+    #[test]
+    fn test_step_ant_trajectory() {
+        let (env, client) = setup();
+        let mut state = (
+            String::from_str(&env, "     \n     \n     \n     \n     "),
+            2,
+            2,
+            0,
+        );
+        let mut positions = [(0u32, 0u32, 0u32); 5];
+        for position in positions.iter_mut() {
+            state = client.step_ant(&state.0, &state.1, &state.2, &state.3);
+            *position = (state.1, state.2, state.3);
+        }
+
+        assert_eq!(
+            positions,
+            [(3, 2, 1), (3, 3, 2), (2, 3, 3), (2, 2, 0), (1, 2, 3)]
+        );
+        assert_eq!(
+            state.0,
+            String::from_str(&env, "     \n     \n   O \n  OO \n     ")
+        );
+    }
+
+    #[test]
+    fn test_handicap_flips_birth() {
+        let (env, client) = setup();
+        // Each birth cell sees two X and one O.
+        let board = String::from_str(&env, "     \n     \n XOX \n     \n     ");
+        let o = b'O' as u32;
+
+        let even = String::from_str(&env, "     \n  X  \n  O  \n  X  \n     ");
+        assert_eq!(client.next_generation_handicap(&board, &o, &0), even);
+
+        let boosted = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        assert_eq!(client.next_generation_handicap(&board, &o, &2), boosted);
+    }
+
+    #[test]
+    fn test_skeleton_thins_block() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "     \n OOO \n OOO \n OOO \n     ");
+        let expected = String::from_str(&env, "     \n     \n  O  \n     \n     ");
+        assert_eq!(client.skeleton(&block), expected);
+
+        let bar = String::from_str(&env, "XXXXX\nXXXXX\nXXXXX");
+        let expected = String::from_str(&env, "     \n XX  \n     ");
+        assert_eq!(client.skeleton(&bar), expected);
+    }
+
+    #[test]
+    fn test_is_spark() {
+        let (env, client) = setup();
+        let single = String::from_str(&env, "   \n O \n   ");
+        assert!(client.is_spark(&single, &3));
+
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert!(!client.is_spark(&block, &3));
+        assert!(!client.is_spark(&block, &u32::MAX));
+    }
+
+    #[test]
+    fn test_dla_grows_aggregate() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "     \n     \n  S  \n     \n     ");
+        let grown = client.next_generation_dla(&board, &(b'S' as u32));
+
+        let mut cells = [0u8; 29];
+        grown.copy_into_slice(&mut cells);
+        // The lone seed would die under Life rules but the aggregate is fixed.
+        assert_eq!(cells[14], b'S');
+        assert!(cells.iter().filter(|&&c| c == b'S').count() > 1);
+    }
+
+    #[test]
+    fn test_lut_matches_conway() {
+        let (env, client) = setup();
+        let conway = Bytes::from_slice(
+            &env,
+            &[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0],
+        );
+        let glider = String::from_str(&env, " O    \n  O   \nOOO   \n      \n      ");
+        assert_eq!(
+            client.next_generation_lut(&glider, &conway),
+            client.next_generation(&glider)
+        );
+
+        let short = Bytes::from_slice(&env, &[0, 0, 0, 1]);
+        let mut two = conway.clone();
+        two.set(3, 2);
+        for lut in [short, two] {
+            assert_eq!(
+                client.try_next_generation_lut(&glider, &lut),
+                Err(Ok(Error::BadLookupTable.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_activity_centroid() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "       \n       \n  OOO  \n       \n       ");
+        assert_eq!(client.activity_centroid(&blinker), (3, 2));
+
+        let block = String::from_str(&env, "OO    \nOO    \n      \n      ");
+        assert_eq!(client.activity_centroid(&block), (3, 2));
+    }
+
+    #[test]
+    fn test_anneal_coarsens_noise() {
+        let (env, client) = setup();
+        let noisy = String::from_str(
+            &env,
+            "OO O OO OO\nOOO OO   O\n O OOOO O \n O OOO OO \nOO  O    O\n OO OOO   \n O   O  O \nO    OO OO\nOOO OOO OO\n    OOO  O",
+        );
+        let mut board = noisy.clone();
+        for _ in 0..4 {
+            board = client.next_generation_anneal(&board);
+        }
+
+        let expected = String::from_str(
+            &env,
+            " O OO O   \nOOOOOOOOO \nOOOOOOOOO \nO O OOOOO \n     OO   \n          \n          \n          \n          \n          ",
+        );
+        assert_eq!(board, expected);
+        assert!(client.perimeter(&board) < client.perimeter(&noisy));
+    }
+
+    #[test]
+    fn test_count_holes() {
+        let (env, client) = setup();
+        let ring = String::from_str(&env, "     \n OOO \n O O \n OOO \n     ");
+        assert_eq!(client.count_holes(&ring), 1);
+
+        let two = String::from_str(&env, "OOOOO\nO O O\nOOOOO");
+        assert_eq!(client.count_holes(&two), 2);
+
+        let open = String::from_str(&env, "OOO\nO  \nOOO");
+        assert_eq!(client.count_holes(&open), 0);
+    }
+
+    #[test]
+    fn test_find_loop_seed() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        assert_eq!(client.find_loop_seed(&blinker, &2, &10), blinker);
+
+        let almost_block = String::from_str(&env, "    \n OO \n O  \n    ");
+        let seed = client.find_loop_seed(&almost_block, &2, &100);
+        assert_ne!(seed, almost_block);
+        assert_eq!(client.next_generation(&client.next_generation(&seed)), seed);
+
+        // The period is capped at `MAX_STEPS`, an even count the blinker loops in.
+        assert_eq!(client.find_loop_seed(&blinker, &u32::MAX, &0), blinker);
+
+        // No single toggle of a lone cell loops, so every allowed attempt runs.
+        let lone = String::from_str(&env, "   \n O \n   ");
+        assert_eq!(client.find_loop_seed(&lone, &2, &u32::MAX), lone);
+    }
+
+    #[test]
+    fn test_find_invalid_cells() {
+        let (env, client) = setup();
+        let board = String::from_bytes(&env, b"O \t\n \0O\n  O");
+        assert_eq!(
+            client.find_invalid_cells(&board),
+            Vec::from_array(&env, [(1u32, 1u32)])
+        );
+
+        let clean = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert!(client.find_invalid_cells(&clean).is_empty());
+    }
+
+    #[test]
+    fn test_step_until_calm() {
+        let (env, client) = setup();
+        // One birth completes the block, then nothing changes.
+        let tromino = String::from_str(&env, "    \n OO \n O  \n    ");
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(
+            client.step_until_calm(&tromino, &0, &10),
+            (block.clone(), 2)
+        );
+        assert_eq!(client.step_until_calm(&tromino, &1, &10), (block, 1));
+
+        // A blinker flips four cells every generation and never calms down.
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let vertical = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        assert_eq!(client.step_until_calm(&blinker, &2, &5), (vertical, 5));
+        assert_eq!(
+            client.step_until_calm(&blinker, &2, &u32::MAX),
+            (blinker, MAX_STEPS)
+        );
+    }
+
+    #[test]
+    fn test_enumerate_successors() {
+        let (env, client) = setup();
+        // The empty corner has one neighbor of each colony: a three-way tie.
+        let board = String::from_str(&env, "XO\nZ ");
+        let successors = client.enumerate_successors(&board, &10);
+        assert_eq!(
+            successors,
+            Vec::from_array(
+                &env,
+                [
+                    String::from_str(&env, "XO\nZX"),
+                    String::from_str(&env, "XO\nZO"),
+                    String::from_str(&env, "XO\nZZ"),
+                ]
+            )
+        );
+        assert_eq!(client.enumerate_successors(&board, &2).len(), 2);
+
+        // Four three-way ties make 81 successors, more than the cap.
+        let ties = String::from_str(&env, "XO  XO  XO  XO \nZ   Z   Z   Z  ");
+        assert_eq!(
+            client.enumerate_successors(&ties, &u32::MAX).len(),
+            MAX_SUCCESSORS
+        );
+
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(
+            client.enumerate_successors(&block, &10),
+            Vec::from_array(&env, [block.clone()])
+        );
+    }
+
+    #[test]
+    fn test_next_generation_xor() {
+        let (env, client) = setup();
+        // Cells 11 and 13 die, cells 7 and 17 are born.
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        assert_eq!(
+            client.next_generation_xor(&blinker),
+            Bytes::from_array(&env, &[0x01, 0x14, 0x40, 0x00])
+        );
+
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(
+            client.next_generation_xor(&block),
+            Bytes::from_array(&env, &[0x00, 0x00])
+        );
+    }
+
+    #[test]
+    fn test_tick_with_harvest() {
+        let (env, client) = setup();
+        // Three X cells are standing when the tick starts; the lone O dies.
+        let board = String::from_str(&env, "      \n XXX  \n      \n     O");
+        let (next, harvested) = client.tick_with_harvest(&board, &(b'X' as u32));
+        assert_eq!(harvested, 3);
+        assert_eq!(
+            next,
+            String::from_str(&env, "  X   \n  X   \n  X   \n      ")
+        );
+
+        let (_, harvested) = client.tick_with_harvest(&board, &(b'O' as u32));
+        assert_eq!(harvested, 1);
+    }
+
+    #[test]
+    fn test_maze_grows_corridors() {
+        let (env, client) = setup();
+        let seed = String::from_str(
+            &env,
+            "       \n       \n  OOO  \n   O   \n       \n       \n       ",
+        );
+        let once = client.next_generation_maze(&seed);
+        assert_eq!(
+            once,
+            String::from_str(
+                &env,
+                "       \n   O   \n  OOO  \n  OOO  \n       \n       \n       "
+            )
+        );
+        // The next generation hollows out a corridor and branches downward.
+        assert_eq!(
+            client.next_generation_maze(&once),
+            String::from_str(
+                &env,
+                "       \n  OOO  \n  O O  \n  OOO  \n   O   \n       \n       "
+            )
+        );
+    }
+
+    #[test]
+    fn test_sorted_live_cells() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "O X\nX O\n O ");
+        let x = b'X' as u32;
+        let o = b'O' as u32;
+        assert_eq!(
+            client.sorted_live_cells(&board),
+            Vec::from_array(
+                &env,
+                [(o, 0, 0), (o, 2, 1), (o, 1, 2), (x, 2, 0), (x, 0, 1)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_next_generation_protected() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        // Protect the top birth site and the blinker's own center.
+        let region = String::from_str(&env, "     \n  #  \n  #  \n     \n     ");
+        assert_eq!(
+            client.next_generation_protected(&blinker, &region),
+            String::from_str(&env, "     \n     \n  O  \n  O  \n     ")
+        );
+
+        let small = String::from_str(&env, "  \n  ");
+        assert_eq!(
+            client.try_next_generation_protected(&blinker, &small),
+            Err(Ok(Error::DimensionMismatch.into()))
+        );
+    }
+
+    #[test]
+    fn test_denoise_clears_noise_before_step() {
+        let (env, client) = setup();
+        // Left alone, the stray cell feeds two births beside the block.
+        let board = String::from_str(&env, "      \n OO O \n OO   \n      ");
+        assert_eq!(
+            client.next_generation(&board),
+            String::from_str(&env, "      \n OOO  \n OOO  \n      ")
+        );
+        assert_eq!(
+            client.next_generation_denoise(&board),
+            String::from_str(&env, "      \n OO   \n OO   \n      ")
+        );
+    }
+
+    #[test]
+    fn test_toroidal_glider_crosses_east_edge() {
+        let (env, client) = setup();
+        let mut board = String::from_str(&env, "      \n    O \n     O\n   OOO\n      \n      ");
+        for _ in 0..4 {
+            board = client.next_generation_toroidal(&board);
+        }
+        assert_eq!(
+            board,
+            String::from_str(&env, "      \n      \n     O\nO     \nO   OO\n      ")
+        );
+    }
+
+    #[test]
+    fn test_toroidal_blinker_on_bottom_row() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n     \n     \n OOO ");
+        let vertical = String::from_str(&env, "  O  \n     \n     \n  O  \n  O  ");
+        assert_eq!(client.next_generation_toroidal(&blinker), vertical);
+        assert_eq!(client.next_generation_toroidal(&vertical), blinker);
+    }
+
+    #[test]
+    fn test_toroidal_glyphs_and_ragged_rows() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n     \n     \n 😀😀😀 ");
+        assert_eq!(
+            client.next_generation_toroidal(&blinker),
+            String::from_str(&env, "  😀  \n     \n     \n  😀  \n  😀  ")
+        );
+
+        let ragged = String::from_str(&env, "OOO\nOO\nOOOO");
+        assert_eq!(
+            client.try_next_generation_toroidal(&ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+        assert_eq!(
+            client.try_next_generation_seeds(&ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+    }
+
+    #[test]
+    fn test_next_generation_n() {
+        let (env, client) = setup();
+        let glider = String::from_str(&env, "      \n  O   \n   O  \n OOO  \n      \n      ");
+        let twice = client.next_generation(&client.next_generation(&glider));
+        assert_eq!(client.next_generation_n(&glider, &2), twice);
+        assert_eq!(client.next_generation_n(&glider, &0), glider);
+
+        // A dying board short-circuits without changing the result.
+        let pair = String::from_str(&env, "   \n OO\n   ");
+        assert_eq!(
+            client.next_generation_n(&pair, &u32::MAX),
+            String::from_str(&env, "   \n   \n   ")
+        );
+    }
+
+    #[test]
+    fn test_colony_bounding_boxes() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "XX    \nX     \n      \n     O\n    OO");
+        let boxes = client.colony_bounding_boxes(&board);
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes.get(b'X' as u32), Some((0, 0, 1, 1)));
+        assert_eq!(boxes.get(b'O' as u32), Some((4, 3, 5, 4)));
+    }
+
+    #[test]
+    fn test_billiard_keeps_wall_pattern_alive() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n     \n     \n OOO ");
+
+        // Against a dead wall the blinker starves in two generations.
+        let dead = client.next_generation(&client.next_generation(&blinker));
+        assert_eq!(
+            dead,
+            String::from_str(&env, "     \n     \n     \n     \n     ")
+        );
+
+        let once = client.next_generation_billiard(&blinker);
+        let settled = String::from_str(&env, "     \n     \n     \n  O  \n O O ");
+        assert_eq!(once, settled);
+        assert_eq!(client.next_generation_billiard(&once), settled);
+
+        let glyphs = String::from_str(&env, "     \n     \n     \n     \n éüé ");
+        assert_eq!(
+            client.next_generation_billiard(&glyphs),
+            client.next_generation_with_boundary(&glyphs, &BoundaryMode::Reflective)
+        );
+        assert_eq!(
+            client.next_generation_billiard(&glyphs),
+            String::from_str(&env, "     \n     \n     \n  é  \n é é ")
+        );
+
+        let ragged = String::from_str(&env, "OOO\nOO\nOOOO");
+        assert_eq!(
+            client.try_next_generation_billiard(&ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+    }
+
+    #[test]
+    fn test_stored_board_steps() {
+        let (env, client) = setup();
+        let horizontal = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let vertical = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        client.init_board(&horizontal);
+        assert_eq!(client.get_board(), horizontal);
+
+        assert_eq!(client.step(), vertical);
+        assert_eq!(client.get_board(), vertical);
+        assert_eq!(client.step(), horizontal);
+        assert_eq!(client.get_board(), horizontal);
+
+        env.as_contract(&client.address, || {
+            let storage = env.storage().persistent();
+            assert_eq!(storage.get(&DataKey::Board), Some(horizontal.clone()));
+            assert!(!env.storage().instance().has(&DataKey::Board));
+        });
+    }
+
+    #[test]
+    fn test_ragged_rows_error() {
+        let (env, client) = setup();
+        let ragged = String::from_str(&env, "OOO\nOO\nOOOO");
+        assert_eq!(
+            client.try_next_generation(&ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+
+        let blinker = String::from_str(&env, "   \nOOO\n   \n");
+        assert_eq!(
+            client.try_next_generation(&blinker),
+            Ok(Ok(String::from_str(&env, " O \n O \n O ")))
+        );
+    }
 
-    for dy in -1i32..=1 {
-        for dx in -1i32..=1 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
+    #[test]
+    fn test_colony_health() {
+        let (env, client) = setup();
+        // Each block cell has three X neighbors; the scattered O cells have none.
+        let board = String::from_str(&env, "XX  O\nXX   \n  O  \n    O");
+        assert_eq!(client.colony_health(&board, &(b'X' as u32)), 38);
+        assert_eq!(client.colony_health(&board, &(b'O' as u32)), 0);
+        assert_eq!(client.colony_health(&board, &(b'Z' as u32)), 0);
+    }
 
-            let nx = x + dx;
-            let ny = y + dy;
+    #[test]
+    fn test_life_without_death() {
+        let (env, client) = setup();
+        // The lone cell, the crowded center, and the corners all survive;
+        // only the three-neighbor sites are born.
+        let board = String::from_str(&env, "O    \n     \n  OOO\n  OOO\n  OOO");
+        assert_eq!(
+            client.next_generation_ldn(&board),
+            String::from_str(&env, "O    \n   O \n  OOO\n OOOO\n  OOO")
+        );
+    }
 
-            if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
-                let cell = grid[(ny as usize) * width + (nx as usize)];
-                if cell != b' ' {
-                    types[count] = cell;
-                    count += 1;
-                }
-            }
+    #[test]
+    fn test_highlife_replicator() {
+        let (env, client) = setup();
+        let highlife = ((1 << 3) | (1 << 6), (1 << 2) | (1 << 3));
+        let mut board = String::from_str(
+            &env,
+            concat!(
+                "              \n",
+                "              \n",
+                "              \n",
+                "              \n",
+                "              \n",
+                "       OOO    \n",
+                "      O  O    \n",
+                "     O   O    \n",
+                "     O  O     \n",
+                "     OOO      \n",
+                "              \n",
+                "              \n",
+                "              \n",
+                "              ",
+            ),
+        );
+        for _ in 0..12 {
+            board = client.next_generation_with_rules(&board, &highlife.0, &highlife.1);
         }
+        // Twelve generations later the replicator has become two copies of itself.
+        assert_eq!(
+            board,
+            String::from_str(
+                &env,
+                concat!(
+                    "              \n",
+                    "              \n",
+                    "              \n",
+                    "     OOO      \n",
+                    "    O  O      \n",
+                    "   O   O      \n",
+                    "   O  O       \n",
+                    "   OOO   OOO  \n",
+                    "        O  O  \n",
+                    "       O   O  \n",
+                    "       O  O   \n",
+                    "       OOO    \n",
+                    "              \n",
+                    "              ",
+                ),
+            )
+        );
     }
 
-    (count as u32, types, count)
-}
+    #[test]
+    fn test_zero_masks_kill_everything() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(
+            client.next_generation_with_rules(&block, &0, &0),
+            String::from_str(&env, "    \n    \n    \n    ")
+        );
+    }
 
-/// Returns the most common cell type among neighbors. Ties are broken randomly.
-fn get_dominant_type(env: &Env, types: &[u8], type_count: usize) -> u8 {
-    if type_count == 0 {
-        return b'O';
+    #[test]
+    fn test_count_population() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.count_population(&block), 4);
+        let full = String::from_str(&env, "OOO\nOOO\nOOO");
+        assert_eq!(client.count_population(&full), 9);
+        assert_eq!(client.count_population(&String::from_str(&env, "")), 0);
+        assert_eq!(
+            client.count_population(&String::from_str(&env, "   \n   ")),
+            0
+        );
     }
-    if type_count == 1 {
-        return types[0];
+
+    #[test]
+    fn test_next_generation_rulestring() {
+        let (env, client) = setup();
+        // The empty center has six neighbors: born under HighLife, not Conway.
+        let board = String::from_str(&env, "     \n OOO \n     \n OOO \n     ");
+        let highlife =
+            client.next_generation_rulestring(&String::from_str(&env, "B36/S23"), &board);
+        assert_eq!(
+            highlife,
+            client.next_generation_with_rules(&board, &0b1001000, &0b1100)
+        );
+        assert_ne!(highlife, client.next_generation(&board));
+
+        let malformed = String::from_str(&env, "B3-S23");
+        assert_eq!(client.next_generation_rulestring(&malformed, &board), board);
     }
 
-    let mut counts: [(u8, u32); 8] = [(0, 0); 8];
-    let mut unique_count = 0usize;
+    #[test]
+    fn test_submit_pattern_keeps_record() {
+        let (env, client) = setup();
+        assert_eq!(client.get_record(), (String::from_str(&env, ""), 0));
 
-    for i in 0..type_count {
-        let t = types[i];
-        let mut found = false;
-        for j in 0..unique_count {
-            if counts[j].0 == t {
-                counts[j].1 += 1;
-                found = true;
-                break;
-            }
-        }
-        if !found {
-            counts[unique_count] = (t, 1);
-            unique_count += 1;
-        }
+        let pair = String::from_str(&env, "   \n OO\n   ");
+        assert_eq!(client.submit_pattern(&pair, &100), 1);
+        assert_eq!(client.get_record(), (pair.clone(), 1));
+
+        // The diagonal shrinks to its center before dying out.
+        let diagonal = String::from_str(&env, "O  \n O \n  O");
+        assert_eq!(client.submit_pattern(&diagonal, &100), 2);
+        assert_eq!(client.get_record(), (diagonal.clone(), 2));
+
+        assert_eq!(client.submit_pattern(&pair, &100), 1);
+        assert_eq!(client.get_record(), (diagonal.clone(), 2));
+
+        env.as_contract(&client.address, || {
+            let storage = env.storage().persistent();
+            assert_eq!(storage.get(&DataKey::Record), Some((diagonal, 2u32)));
+            assert!(!env.storage().instance().has(&DataKey::Record));
+        });
     }
 
-    let mut max_count = 0u32;
-    for i in 0..unique_count {
-        if counts[i].1 > max_count {
-            max_count = counts[i].1;
-        }
+    #[test]
+    fn test_count_by_type() {
+        let (env, client) = setup();
+        let counts = client.count_by_type(&String::from_str(&env, " XO \n OX "));
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts.get(b'X' as u32), Some(2));
+        assert_eq!(counts.get(b'O' as u32), Some(2));
+        assert_eq!(counts.get(b' ' as u32), None);
+    }
+
+    #[test]
+    fn test_is_stable() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert!(client.is_stable(&block));
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        assert!(!client.is_stable(&blinker));
+    }
+
+    #[test]
+    fn test_seeds_rule() {
+        let (env, client) = setup();
+        // All three live cells die; births land only on two-neighbor cells.
+        let board = String::from_str(&env, "      \n OO   \n      \n    O \n      ");
+        assert_eq!(
+            client.next_generation_seeds(&board),
+            String::from_str(&env, " OO   \n      \n OOO  \n      \n      ")
+        );
+    }
+
+    #[test]
+    fn test_diag_inherit() {
+        let (env, client) = setup();
+        // The center is born next to two orthogonal O cells and one diagonal X.
+        let board = String::from_str(&env, "XO \nO  \n   ");
+        assert_eq!(
+            client.next_generation(&board),
+            String::from_str(&env, "XO \nOO \n   ")
+        );
+        assert_eq!(
+            client.next_generation_diag_inherit(&board),
+            String::from_str(&env, "XO \nOX \n   ")
+        );
+    }
+
+    #[test]
+    fn test_find_period() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        assert_eq!(client.find_period(&blinker, &10), 2);
+        assert_eq!(client.find_period(&blinker, &1), 0);
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.find_period(&block, &10), 1);
     }
 
-    let mut winners: [u8; 8] = [0; 8];
-    let mut winner_count = 0usize;
-    for i in 0..unique_count {
-        if counts[i].1 == max_count {
-            winners[winner_count] = counts[i].0;
-            winner_count += 1;
+    #[test]
+    fn test_deterministic_tie_break() {
+        let (env, client) = setup();
+        // A birth needs three neighbors, so a tie is always three colonies of one.
+        let board = String::from_str(&env, "XO\nZ ");
+        for _ in 0..5 {
+            assert_eq!(
+                client.next_generation_deterministic(&board),
+                String::from_str(&env, "XO\nZO")
+            );
         }
     }
 
-    if winner_count == 1 {
-        return winners[0];
+    #[test]
+    fn test_frontline() {
+        let (env, client) = setup();
+        let board = String::from_str(&env, "X O\nX O\n   ");
+        assert_eq!(
+            client.frontline(&board, &(b'X' as u32), &(b'O' as u32)),
+            Vec::from_array(&env, [(1u32, 0u32), (1, 1), (1, 2)])
+        );
+        assert!(client
+            .frontline(&board, &(b'X' as u32), &(b'Z' as u32))
+            .is_empty());
     }
 
-    let index = env.prng().gen_range::<u64>(0..winner_count as u64) as usize;
-    winners[index]
-}
+    #[test]
+    fn test_generation_in_slices() {
+        let (env, client) = setup();
+        let glider = String::from_str(&env, "      \n  O   \n   O  \n OOO  \n      \n      ");
+        client.init_board(&glider);
 
-#[contract]
-pub struct GameOfLife;
+        client.begin_generation();
+        assert!(!client.advance_slice(&3));
+        assert!(client.advance_slice(&3));
+        client.commit_generation();
+        assert_eq!(client.get_board(), client.next_generation(&glider));
 
-#[contractimpl]
-impl GameOfLife {
-    /// Computes the next generation of Conway's Game of Life.
-    /// Board format: rows separated by newlines, space = dead, any other char = alive.
-    /// Newly born cells inherit the dominant neighbor type; ties are broken randomly.
-    pub fn next_generation(env: Env, board: String) -> String {
-        let len = board.len() as usize;
-        if len == 0 || len > MAX_BOARD_SIZE {
-            return board;
+        let block = String::from_str(&env, "    \n éé \n éé \n    ");
+        client.init_board(&block);
+        client.begin_generation();
+        assert!(client.advance_slice(&4));
+        client.commit_generation();
+        assert_eq!(client.get_board(), block);
+        assert_eq!(client.get_generation(), 1);
+
+        // Replacing or stepping the board abandons the generation in progress.
+        client.begin_generation();
+        client.init_board(&glider);
+        assert!(client.try_commit_generation().is_err());
+        assert_eq!(client.get_board(), glider);
+        assert_eq!(client.get_generation(), 0);
+
+        client.begin_generation();
+        assert!(client.advance_slice(&6));
+        let stepped = client.step();
+        assert!(client.try_commit_generation().is_err());
+        assert_eq!(client.get_board(), stepped);
+        assert_eq!(client.get_generation(), 1);
+
+        client.init_board(&String::from_str(&env, "OOO\nOO"));
+        assert_eq!(
+            client.try_begin_generation(),
+            Err(Ok(Error::RaggedRows.into()))
+        );
+    }
+
+    #[test]
+    fn test_next_generation_seeded() {
+        let (env, client) = setup();
+        // Four independent three-way ties.
+        let board = String::from_str(&env, "XO  XO  XO  XO \nZ   Z   Z   Z  ");
+        let first = client.next_generation_seeded(&board, &1);
+        assert_eq!(client.next_generation_seeded(&board, &1), first);
+        assert_ne!(client.next_generation_seeded(&board, &2), first);
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        let (env, client) = setup();
+        let glider = String::from_str(&env, "     \n  O  \n   O \n OOO \n     ");
+        let rle = client.board_to_rle(&glider);
+        assert_eq!(rle, String::from_str(&env, "x = 5, y = 5\n$2bo$3bo$b3o!"));
+        assert_eq!(client.rle_to_board(&rle), glider);
+
+        let blinker = String::from_str(&env, "     \n     \n XXX \n     \n     ");
+        let rle = client.board_to_rle(&blinker);
+        assert_eq!(rle, String::from_str(&env, "x = 5, y = 5\n2$b3X!"));
+        assert_eq!(client.rle_to_board(&rle), blinker);
+    }
+
+    #[test]
+    fn test_board_to_rle_rejects_token_colonies() {
+        let (env, client) = setup();
+        for board in ["bb\nbb", "oo\noo", " 1\n  ", "$ \n  ", " !\n  "] {
+            assert_eq!(
+                client.try_board_to_rle(&String::from_str(&env, board)),
+                Err(Ok(Error::NoRleTag.into()))
+            );
         }
 
-        let mut buffer = [0u8; MAX_BOARD_SIZE];
-        board.copy_into_slice(&mut buffer[..len]);
-        let input = &buffer[..len];
+        let board = String::from_str(&env, "BB \nO B\n  B");
+        let rle = client.board_to_rle(&board);
+        assert_eq!(rle, String::from_str(&env, "x = 3, y = 3\n2B$obB$2bB!"));
+        assert_eq!(client.rle_to_board(&rle), board);
+    }
+
+    #[test]
+    fn test_rle_to_board_database_pattern() {
+        let (env, client) = setup();
+        let rle = String::from_str(&env, "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!");
+        assert_eq!(
+            client.rle_to_board(&rle),
+            String::from_str(&env, " O \n  O\nOOO")
+        );
+        let headerless = String::from_str(&env, "bo$2bo$3o!");
+        assert_eq!(
+            client.rle_to_board(&headerless),
+            String::from_str(&env, " O \n  O\nOOO")
+        );
+        let malformed = String::from_bytes(&env, b"2o\x01!");
+        assert_eq!(client.rle_to_board(&malformed), String::from_str(&env, ""));
+    }
 
-        let mut width: usize = 0;
-        let mut height: usize = 0;
-        let mut current_width: usize = 0;
+    #[test]
+    fn test_next_generation_neighborhood() {
+        let (env, client) = setup();
+        let cross = String::from_str(&env, "     \n  O  \n OOO \n  O  \n     ");
+        let moore = client.next_generation_neighborhood(&cross, &Neighborhood::Moore);
+        assert_eq!(
+            moore,
+            String::from_str(&env, "     \n OOO \n O O \n OOO \n     ")
+        );
+        assert_eq!(moore, client.next_generation(&cross));
 
-        for &b in input.iter() {
-            if b == b'\n' {
-                if width == 0 {
-                    width = current_width;
-                }
-                height += 1;
-                current_width = 0;
-            } else {
-                current_width += 1;
-            }
+        // Orthogonally the center is crowded, the arms are lonely, and no dead
+        // cell has three neighbors.
+        assert_eq!(
+            client.next_generation_neighborhood(&cross, &Neighborhood::VonNeumann),
+            String::from_str(&env, "     \n     \n     \n     \n     ")
+        );
+    }
+
+    #[test]
+    fn test_next_generation_range() {
+        let (env, client) = setup();
+        // Only the center sees all four corners within radius 2.
+        let corners = String::from_str(&env, "O   O\n     \n     \n     \nO   O");
+        assert_eq!(
+            client.next_generation_range(&corners, &2, &(1 << 4), &0),
+            String::from_str(&env, "     \n     \n  O  \n     \n     ")
+        );
+
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let (birth, survive) = (0b111000, 0b11100);
+        assert_eq!(
+            client.next_generation_range(&blinker, &2, &birth, &survive),
+            String::from_str(&env, " OOO \n OOO \n OOO \n OOO \n OOO ")
+        );
+        assert_eq!(
+            client.next_generation_range(&blinker, &1, &birth, &survive),
+            String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ")
+        );
+        assert_eq!(
+            client.next_generation_range(&blinker, &3, &birth, &survive),
+            String::from_str(&env, "OOOOO\nOOOOO\nOOOOO\nOOOOO\nOOOOO")
+        );
+        assert_eq!(
+            client.try_next_generation_range(&blinker, &4, &birth, &survive),
+            Err(Ok(Error::UnsupportedRadius.into()))
+        );
+    }
+
+    #[test]
+    fn test_stamp() {
+        let (env, client) = setup();
+        let field = String::from_str(&env, "      \n      \n      \n      \n      \n      ");
+        let glider = String::from_str(&env, " O \n  O\nOOO");
+        assert_eq!(
+            client.stamp(&field, &glider, &0, &0),
+            String::from_str(&env, " O    \n  O   \nOOO   \n      \n      \n      ")
+        );
+
+        // Stamped past the corner, only the glider's top-left survives.
+        let stamped = client.stamp(&field, &glider, &4, &4);
+        assert_eq!(
+            stamped,
+            String::from_str(&env, "      \n      \n      \n      \n     O\n      ")
+        );
+
+        // Dead pattern cells leave the board beneath them alone.
+        let block = String::from_str(&env, "XX\nXX");
+        assert_eq!(
+            client.stamp(&block, &glider, &0, &0),
+            String::from_str(&env, "XO\nXX")
+        );
+    }
+
+    #[test]
+    fn test_crop_to_live() {
+        let (env, client) = setup();
+        let mut raw = [b' '; 109];
+        for y in 1..10 {
+            raw[y * 11 - 1] = b'\n';
         }
-        if current_width > 0 {
-            if width == 0 {
-                width = current_width;
-            }
-            height += 1;
+        for &(x, y) in &[(4, 4), (5, 4), (4, 5), (5, 5)] {
+            raw[y * 11 + x] = b'O';
         }
+        let board = String::from_bytes(&env, &raw);
+        assert_eq!(
+            client.crop_to_live(&board),
+            String::from_str(&env, "OO\nOO")
+        );
 
-        if width == 0 || height == 0 {
-            return board;
+        let dead = String::from_str(&env, "   \n   ");
+        assert_eq!(client.crop_to_live(&dead), String::from_str(&env, ""));
+    }
+
+    #[test]
+    fn test_walls_block_blinker() {
+        let (env, client) = setup();
+        let wall = b'#' as u32;
+        // The wall sits on the blinker's upper birth site.
+        let board = String::from_str(&env, "     \n  #  \n OOO \n     \n     ");
+        let once = client.next_generation_with_walls(&board, &wall);
+        assert_eq!(
+            once,
+            String::from_str(&env, "     \n  #  \n  O  \n  O  \n     ")
+        );
+        let twice = client.next_generation_with_walls(&once, &wall);
+        assert_eq!(
+            twice,
+            String::from_str(&env, "     \n  #  \n     \n     \n     ")
+        );
+        assert_eq!(client.next_generation_with_walls(&twice, &wall), twice);
+
+        for wall in [b' ', b'\t', b'\n', b'\r'] {
+            assert_eq!(
+                client.try_next_generation_with_walls(&board, &(wall as u32)),
+                Err(Ok(Error::BadWall.into()))
+            );
         }
+    }
 
-        let mut grid = [0u8; MAX_BOARD_SIZE];
-        let mut idx = 0usize;
-        for &b in input.iter() {
-            if b != b'\n' {
-                grid[idx] = b;
-                idx += 1;
-            }
+    #[test]
+    fn test_rotate_and_flip() {
+        let (env, client) = setup();
+        let horizontal = String::from_str(&env, "   \nOOO\n   ");
+        assert_eq!(
+            client.rotate_cw(&horizontal),
+            String::from_str(&env, " O \n O \n O ")
+        );
+
+        let board = String::from_str(&env, "XO \n  Z");
+        assert_eq!(
+            client.rotate_cw(&board),
+            String::from_str(&env, " X\n O\nZ ")
+        );
+        assert_eq!(
+            client.flip_horizontal(&board),
+            String::from_str(&env, " OX\nZ  ")
+        );
+        assert_eq!(
+            client.flip_vertical(&board),
+            String::from_str(&env, "  Z\nXO ")
+        );
+
+        let half_turn = client.rotate_cw(&client.rotate_cw(&board));
+        assert_eq!(
+            half_turn,
+            client.flip_vertical(&client.flip_horizontal(&board))
+        );
+    }
+
+    #[test]
+    fn test_next_generation_diff() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let (born, died) = (b'O' as u32, b' ' as u32);
+        assert_eq!(
+            client.next_generation_diff(&blinker),
+            Vec::from_array(
+                &env,
+                [(2, 1, born), (1, 2, died), (3, 2, died), (2, 3, born)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_next_generation_growing() {
+        let (env, client) = setup();
+        // The glider touches the west and south edges, so both gain a column
+        // or row before it steps.
+        let glider = String::from_str(&env, "    \n O  \n  O \nOOO ");
+        assert_eq!(
+            client.next_generation_growing(&glider),
+            String::from_str(&env, "     \n     \n O O \n  OO \n  O  ")
+        );
+        assert_eq!(
+            client.next_generation(&glider),
+            String::from_str(&env, "    \n    \nO O \n OO ")
+        );
+
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        assert_eq!(client.next_generation_growing(&block), block);
+    }
+
+    #[test]
+    fn test_neighbors_at() {
+        let (env, client) = setup();
+        let full = String::from_str(&env, "OOO\nOOO\nOOO");
+        assert_eq!(client.neighbors_at(&full, &1, &1), 8);
+        assert_eq!(client.neighbors_at(&full, &0, &0), 3);
+        assert_eq!(client.neighbors_at(&full, &2, &1), 5);
+        assert_eq!(client.neighbors_at(&full, &3, &0), 0);
+    }
+
+    #[test]
+    fn test_generation_counter() {
+        let (env, client) = setup();
+        assert_eq!(client.get_generation(), 0);
+        client.init_board(&String::from_str(&env, "     \n     \n OOO \n     \n     "));
+        for _ in 0..3 {
+            client.step();
         }
+        assert_eq!(client.get_generation(), 3);
+
+        client.init_board(&String::from_str(&env, "   \n   "));
+        assert_eq!(client.get_generation(), 0);
+        client.step();
+        client.reset();
+        assert_eq!(client.get_generation(), 0);
+        assert!(client.try_get_board().is_err());
+    }
 
-        let mut result = Bytes::new(&env);
+    #[test]
+    fn test_multibyte_colony_glyphs() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "    \n éé \n éé \n    ");
+        assert_eq!(client.next_generation(&block), block);
 
-        for y in 0..height {
-            if y > 0 {
-                result.push_back(b'\n');
-            }
-            for x in 0..width {
-                let current_char = grid[y * width + x];
-                let cell_alive = current_char != b' ';
-                let (neighbors, neighbor_types, type_count) =
-                    get_neighbor_info(&grid, x as i32, y as i32, width, height);
+        // The newborn completing the block takes the two-byte glyph too.
+        let tromino = String::from_str(&env, "    \n éé \n é  \n    ");
+        assert_eq!(client.next_generation(&tromino), block);
 
-                let next_alive = if cell_alive {
-                    neighbors == 2 || neighbors == 3
-                } else {
-                    neighbors == 3
-                };
+        let blinker = String::from_str(&env, "   \n😀😀😀\n   ");
+        assert_eq!(
+            client.next_generation(&blinker),
+            String::from_str(&env, " 😀 \n 😀 \n 😀 ")
+        );
+    }
 
-                if next_alive {
-                    if cell_alive {
-                        result.push_back(current_char);
-                    } else {
-                        let new_type =
-                            get_dominant_type(&env, &neighbor_types[..type_count], type_count);
-                        result.push_back(new_type);
-                    }
-                } else {
-                    result.push_back(b' ');
-                }
+    #[test]
+    fn test_multibyte_result_too_large() {
+        let (env, client) = setup();
+        // A full row of four-byte glyphs between two blank rows fills both
+        // with newborns, roughly doubling the board's 60,002 bytes.
+        let width = 10_000;
+        let mut text = alloc::vec::Vec::new();
+        text.extend(core::iter::repeat_n(b' ', width));
+        text.push(b'\n');
+        for _ in 0..width {
+            text.extend_from_slice("😀".as_bytes());
+        }
+        text.push(b'\n');
+        text.extend(core::iter::repeat_n(b' ', width));
+        let board = String::from_bytes(&env, &text);
+        assert_eq!(
+            client.try_next_generation(&board),
+            Err(Ok(Error::ResultTooLarge.into()))
+        );
+        assert_eq!(
+            client.try_next_generation_output_len(&board),
+            Err(Ok(Error::ResultTooLarge.into()))
+        );
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let (env, client) = setup();
+        assert_eq!(
+            client.dimensions(&String::from_str(&env, "OOO\nOOO")),
+            (3, 2)
+        );
+        assert_eq!(client.dimensions(&String::from_str(&env, "OO")), (2, 1));
+        assert_eq!(
+            client.dimensions(&String::from_str(&env, "OO\n  \n")),
+            (2, 2)
+        );
+        assert_eq!(client.dimensions(&String::from_str(&env, "é \n é")), (2, 2));
+        assert_eq!(client.dimensions(&String::from_str(&env, "")), (0, 0));
+    }
+
+    #[test]
+    fn test_validate() {
+        let (env, client) = setup();
+        assert!(client.validate(&String::from_str(&env, "    \n OO \n OO \n    ")));
+        assert!(!client.validate(&String::from_str(&env, "OOO\nOO\nOOOO")));
+        assert!(!client.validate(&String::from_bytes(&env, &[b'O'; MAX_BOARD_SIZE + 1])));
+        assert!(!client.validate(&String::from_bytes(&env, b"O\x07O\nOOO")));
+        assert!(!client.validate(&String::from_bytes(&env, b"O\x7fO\nOOO")));
+        assert!(client.validate(&String::from_str(&env, "O~é\nOOO")));
+    }
+
+    #[test]
+    fn test_large_board() {
+        let (env, client) = setup();
+        // A 200x200 field of blocks, each 2x2 and two cells from the next, is a
+        // still life, so the board comes back unchanged.
+        let mut text = alloc::vec::Vec::new();
+        for y in 0..200 {
+            if y > 0 {
+                text.push(b'\n');
+            }
+            for x in 0..200 {
+                let live = x % 4 != 0 && x % 4 != 3 && y % 4 != 0 && y % 4 != 3;
+                text.push(if live { b'O' } else { b' ' });
             }
         }
+        let board = String::from_bytes(&env, &text);
+        assert_eq!(client.next_generation(&board), board);
+    }
+
+    #[test]
+    fn test_next_generation_weighted() {
+        let (env, client) = setup();
+        // The center is born from one O and two X; O's weight of 3 outscores
+        // X's 2.
+        let board = String::from_str(&env, "O X\n   \n  X");
+        let mut weights = Map::new(&env);
+        weights.set(b'O' as u32, 3);
+        weights.set(b'X' as u32, 1);
+        let result = client.next_generation_weighted(&board, &weights);
+        assert_eq!(result, String::from_str(&env, "   \n O \n   "));
 
-        let result_len = result.len() as usize;
-        let mut result_buffer = [0u8; MAX_BOARD_SIZE];
-        result.copy_into_slice(&mut result_buffer[..result_len]);
-        String::from_bytes(&env, &result_buffer[..result_len])
+        let result = client.next_generation_weighted(&board, &Map::new(&env));
+        assert_eq!(result, String::from_str(&env, "   \n X \n   "));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::Env;
+    #[test]
+    fn test_next_generation_aging() {
+        let (env, client) = setup();
+        let block = String::from_str(&env, "    \n OO \n OO \n    ");
+        let (mut board, mut ages) = (block.clone(), Bytes::new(&env));
+        for _ in 0..3 {
+            (board, ages) = client.next_generation_aging(&board, &ages, &3);
+            assert_eq!(board, block);
+        }
+        let mut expected = [0u8; 16];
+        for i in [5, 6, 9, 10] {
+            expected[i] = 3;
+        }
+        assert_eq!(ages, Bytes::from_array(&env, &expected));
 
-    fn setup() -> (Env, GameOfLifeClient<'static>) {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, GameOfLife);
-        let client = GameOfLifeClient::new(&env, &contract_id);
-        (env, client)
+        let (board, ages) = client.next_generation_aging(&board, &ages, &3);
+        assert_eq!(board, String::from_str(&env, "    \n    \n    \n    "));
+        assert_eq!(ages, Bytes::from_array(&env, &[0u8; 16]));
+
+        // Without ages, every cell starts over at age 0.
+        let (board, _) = client.next_generation_aging(&block, &Bytes::new(&env), &3);
+        assert_eq!(board, block);
     }
 
     #[test]
-    fn test_empty_board() {
+    fn test_next_generation_seniority() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "     \n     \n     ");
-        assert_eq!(client.next_generation(&board), board);
+        // The cell below the left O/X pair is born from one O, one X, and one
+        // Z. X has 10 cells on the board, O has 3, and Z has 1, so X wins.
+        let board = String::from_str(
+            &env,
+            "O X   XX  XX\n      XX  XX\n Z          \n            \nO   O      X",
+        );
+        let result = client.next_generation_seniority(&board);
+        assert_eq!(
+            result,
+            String::from_str(
+                &env,
+                "      XX  XX\n X    XX  XX\n            \n            \n            "
+            )
+        );
     }
 
     #[test]
-    fn test_block_still_life() {
+    fn test_step_publishes_colony_changes() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{vec, IntoVal};
+
         let (env, client) = setup();
-        let board = String::from_str(&env, "    \n OO \n OO \n    ");
-        assert_eq!(client.next_generation(&board), board);
+        client.init_board(&String::from_str(&env, "     \n  O  \n  O  \n  O  \n     "));
+        client.step();
+
+        let mut changes: Map<u32, (u32, u32, u32)> = Map::new(&env);
+        changes.set(b'O' as u32, (2, 2, 1));
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (symbol_short!("life"), symbol_short!("gen")).into_val(&env),
+                    (1u32, changes).into_val(&env),
+                ),
+            ]
+        );
     }
 
     #[test]
-    fn test_blinker_oscillator() {
+    fn test_random_board() {
         let (env, client) = setup();
-        let horizontal = String::from_str(&env, "     \n     \n OOO \n     \n     ");
-        let vertical = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        let types = Bytes::from_slice(&env, b"XO");
+        let board = client.random_board(&4, &3, &100, &types);
+        assert_eq!(client.dimensions(&board), (4, 3));
+        assert_eq!(client.count_population(&board), 12);
+        let counts = client.count_by_type(&board);
+        assert_eq!(
+            counts.get(b'X' as u32).unwrap_or(0) + counts.get(b'O' as u32).unwrap_or(0),
+            12
+        );
 
-        assert_eq!(client.next_generation(&horizontal), vertical);
-        assert_eq!(client.next_generation(&vertical), horizontal);
+        let board = client.random_board(&4, &3, &0, &types);
+        assert_eq!(board, String::from_str(&env, "    \n    \n    "));
+
+        let empty = String::from_str(&env, "");
+        assert_eq!(client.random_board(&4, &3, &50, &Bytes::new(&env)), empty);
+        assert_eq!(client.random_board(&1_000, &1_000, &50, &types), empty);
     }
 
     #[test]
-    fn test_single_cell_dies() {
+    fn test_spawn_pattern() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "   \n O \n   ");
-        let expected = String::from_str(&env, "   \n   \n   ");
-        assert_eq!(client.next_generation(&board), expected);
+        let name = String::from_str(&env, "blinker");
+        let board = client.spawn_pattern(&name, &5, &5);
+        assert_eq!(
+            board,
+            String::from_str(&env, "     \n     \n OOO \n     \n     ")
+        );
+        assert_eq!(
+            client.next_generation(&board),
+            String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ")
+        );
+
+        let gun = String::from_str(&env, "glider_gun");
+        assert_eq!(
+            client.dimensions(&client.spawn_pattern(&gun, &40, &12)),
+            (40, 12)
+        );
+
+        let empty = String::from_str(&env, "");
+        assert_eq!(client.spawn_pattern(&gun, &10, &10), empty);
+        let unknown = String::from_str(&env, "pulsar");
+        assert_eq!(client.spawn_pattern(&unknown, &20, &20), empty);
     }
 
     #[test]
-    fn test_overcrowding() {
+    fn test_bitmap_round_trip() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "OOO\nOOO\nOOO");
-        let expected = String::from_str(&env, "O O\n   \nO O");
-        assert_eq!(client.next_generation(&board), expected);
+        let board = String::from_str(&env, "     \n     \n OOO \n     \n     ");
+        let bitmap = client.board_to_bitmap(&board);
+        assert_eq!(
+            bitmap,
+            Bytes::from_slice(&env, &[0, 0, 0, 5, 0, 0, 0, 5, 0, 0x1c, 0, 0])
+        );
+        assert_eq!(client.bitmap_to_board(&bitmap, &(b'O' as u32)), board);
+
+        // Other colonies come back as `live_char`.
+        let mixed = String::from_str(&env, "XO");
+        let bitmap = client.board_to_bitmap(&mixed);
+        let result = client.bitmap_to_board(&bitmap, &(b'#' as u32));
+        assert_eq!(result, String::from_str(&env, "##"));
+
+        let empty = String::from_str(&env, "");
+        assert_eq!(client.bitmap_to_board(&bitmap, &(b' ' as u32)), empty);
+        let truncated = bitmap.slice(..8);
+        assert_eq!(client.bitmap_to_board(&truncated, &(b'O' as u32)), empty);
     }
 
     #[test]
-    fn test_birth() {
+    fn test_next_generation_batch() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "    \n O  \n OO \n    ");
-        let expected = String::from_str(&env, "    \n OO \n OO \n    ");
-        assert_eq!(client.next_generation(&board), expected);
+        let boards = soroban_sdk::vec![
+            &env,
+            String::from_str(&env, "     \n  O  \n  O  \n  O  \n     "),
+            String::from_str(&env, "XX\nXX"),
+            String::from_str(&env, ""),
+        ];
+        let results = client.next_generation_batch(&boards);
+        assert_eq!(
+            results,
+            soroban_sdk::vec![
+                &env,
+                String::from_str(&env, "     \n     \n OOO \n     \n     "),
+                String::from_str(&env, "XX\nXX"),
+                String::from_str(&env, ""),
+            ]
+        );
+
+        let with_ragged = soroban_sdk::vec![
+            &env,
+            String::from_str(&env, "XX\nXX"),
+            String::from_str(&env, "OOO\nOO"),
+        ];
+        assert_eq!(
+            client.try_next_generation_batch(&with_ragged),
+            Err(Ok(Error::RaggedRows.into()))
+        );
     }
 
     #[test]
-    fn test_dominant_type_clear_winner() {
+    fn test_crlf_line_endings() {
         let (env, client) = setup();
-        // Two X neighbors vs one O neighbor - new cell should be X
-        let board = String::from_str(&env, "   \n X \nX O\n   ");
-        let expected = String::from_str(&env, "   \n X \n X \n   ");
-        assert_eq!(client.next_generation(&board), expected);
+        let crlf = String::from_str(&env, "    \r\n OOO\r\nOOO \r\n    \r\n");
+        let lf = String::from_str(&env, "    \n OOO\nOOO \n    \n");
+        let expected = String::from_str(&env, "  O \nO  O\nO  O\n O  ");
+        assert_eq!(client.next_generation(&crlf), expected);
+        assert_eq!(client.next_generation(&lf), expected);
+
+        // Rows of different widths are ragged whatever the line endings.
+        let crlf = String::from_str(&env, "OOO\r\n OOO\r\n");
+        let lf = String::from_str(&env, "OOO\n OOO\n");
+        let ragged = Err(Ok(Error::RaggedRows.into()));
+        assert_eq!(client.try_next_generation(&crlf), ragged);
+        assert_eq!(client.try_next_generation(&lf), ragged);
     }
 
     #[test]
-    fn test_mixed_types_block_survives() {
+    fn test_next_generation_with_boundary() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "    \n XO \n OX \n    ");
-        assert_eq!(client.next_generation(&board), board);
+        let board = String::from_str(&env, "   \nO  \nO  \nO  \n   ");
+
+        let dead = client.next_generation_with_boundary(&board, &BoundaryMode::Dead);
+        assert_eq!(dead, String::from_str(&env, "   \n   \nOO \n   \n   "));
+        assert_eq!(dead, client.next_generation(&board));
+
+        let reflective = client.next_generation_with_boundary(&board, &BoundaryMode::Reflective);
+        assert_eq!(
+            reflective,
+            String::from_str(&env, "   \nO  \n O \nO  \n   ")
+        );
+        assert_eq!(reflective, client.next_generation_billiard(&board));
+
+        let toroidal = client.next_generation_with_boundary(&board, &BoundaryMode::Toroidal);
+        assert_eq!(toroidal, client.next_generation_toroidal(&board));
     }
 
     #[test]
-    fn test_same_type_blinker() {
+    fn test_next_generation_stats() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "     \n     \n XXX \n     \n     ");
-        let expected = String::from_str(&env, "     \n  X  \n  X  \n  X  \n     ");
-        assert_eq!(client.next_generation(&board), expected);
+        let board = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        let (result, births, deaths, survivors) = client.next_generation_stats(&board);
+        assert_eq!(
+            result,
+            String::from_str(&env, "     \n     \n OOO \n     \n     ")
+        );
+        assert_eq!((births, deaths, survivors), (2, 2, 1));
     }
 
     #[test]
-    fn test_birth_inherits_neighbor_type() {
+    fn test_next_generation_checked() {
         let (env, client) = setup();
-        let board = String::from_str(&env, "     \n     \n YYY \n     \n     ");
-        let expected = String::from_str(&env, "     \n  Y  \n  Y  \n  Y  \n     ");
-        assert_eq!(client.next_generation(&board), expected);
+        let board = String::from_str(&env, "   \n O \n   ");
+        let (result, extinct) = client.next_generation_checked(&board);
+        assert_eq!(result, String::from_str(&env, "   \n   \n   "));
+        assert!(extinct);
+
+        let board = String::from_str(&env, "OO\nOO");
+        assert_eq!(client.next_generation_checked(&board), (board, false));
+    }
+
+    #[test]
+    fn test_population_series() {
+        let (env, client) = setup();
+        let blinker = String::from_str(&env, "     \n  O  \n  O  \n  O  \n     ");
+        let series = client.population_series(&blinker, &4);
+        assert_eq!(series, soroban_sdk::vec![&env, 3, 3, 3, 3]);
+
+        let lone = String::from_str(&env, "   \n O \n   ");
+        let series = client.population_series(&lone, &3);
+        assert_eq!(series, soroban_sdk::vec![&env, 0, 0, 0]);
+
+        let empty = String::from_str(&env, "");
+        assert_eq!(client.population_series(&empty, &3), Vec::new(&env));
     }
 }
@@ -0,0 +1,36 @@
+//! Well-known patterns that `spawn_pattern` places by name, in board format.
+//! They match the patterns the frontend offers.
+
+/// Every named pattern.
+const PATTERNS: [(&str, &str); 7] = [
+    ("glider", " O \n  O\nOOO"),
+    ("lwss", " O  O\nO    \nO   O\nOOOO "),
+    ("blinker", "OOO"),
+    ("block", "OO\nOO"),
+    ("beacon", "OO  \nO   \n   O\n  OO"),
+    ("toad", " OOO\nOOO "),
+    (
+        "glider_gun",
+        "                        O           \n                      O O           \n            OO      OO            OO\n           O   O    OO            OO\nOO        O     O   OO              \nOO        O   O OO    O O           \n          O     O       O           \n           O   O                    \n            OO                      ",
+    ),
+];
+
+/// Returns the pattern called `name`, if there is one.
+pub(crate) fn find(name: &[u8]) -> Option<&'static [u8]> {
+    PATTERNS
+        .iter()
+        .find(|(n, _)| n.as_bytes() == name)
+        .map(|(_, pattern)| pattern.as_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(b"block"), Some(&b"OO\nOO"[..]));
+        assert_eq!(find(b"Block"), None);
+        assert_eq!(find(b""), None);
+    }
+}
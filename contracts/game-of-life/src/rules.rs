@@ -0,0 +1,472 @@
+//! Cell-level rules shared by the generation variants: neighbor scanning,
+//! newborn colony selection, and the birth/survival transition.
+use soroban_sdk::{contracttype, Bytes, Env};
+
+/// Which surrounding cells count as a cell's neighbors.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighborhood {
+    /// All eight surrounding cells.
+    Moore,
+    /// The four orthogonal cells: north, south, east, and west.
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// Offsets from a cell to each of its neighbors.
+    fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::Moore => &MOORE,
+            Neighborhood::VonNeumann => &ORTHOGONAL,
+        }
+    }
+}
+
+/// Outer-totalistic birth/survival rule: bit `n` of a mask means a cell with `n`
+/// live neighbors is born (`birth`) or stays alive (`survival`).
+#[derive(Clone, Copy)]
+pub(crate) struct Rule {
+    pub(crate) birth: u32,
+    pub(crate) survival: u32,
+    pub(crate) neighborhood: Neighborhood,
+}
+
+impl Rule {
+    /// A rule over the Moore neighborhood.
+    pub(crate) const fn new(birth: u32, survival: u32) -> Rule {
+        Rule {
+            birth,
+            survival,
+            neighborhood: Neighborhood::Moore,
+        }
+    }
+
+    /// Conway's B3/S23.
+    pub(crate) const CONWAY: Rule = Rule::new(1 << 3, (1 << 2) | (1 << 3));
+
+    /// Anneal, B4678/S35678: majority voting with a twist that coarsens domains.
+    pub(crate) const ANNEAL: Rule = Rule::new(
+        (1 << 4) | (1 << 6) | (1 << 7) | (1 << 8),
+        (1 << 3) | (1 << 5) | (1 << 6) | (1 << 7) | (1 << 8),
+    );
+
+    /// Maze, B3/S12345: seeds grow into branching corridors.
+    pub(crate) const MAZE: Rule =
+        Rule::new(1 << 3, (1 << 1) | (1 << 2) | (1 << 3) | (1 << 4) | (1 << 5));
+
+    /// Life without Death, B3/S012345678: births as in Conway, and live cells
+    /// never die.
+    pub(crate) const LIFE_WITHOUT_DEATH: Rule = Rule::new(1 << 3, 0x1ff);
+
+    /// Seeds, B2/S: every live cell dies each generation, and dead cells with
+    /// exactly two neighbors are born.
+    pub(crate) const SEEDS: Rule = Rule::new(1 << 2, 0);
+
+    /// Parses a Golly-style `B<digits>/S<digits>` rulestring such as `B36/S23`.
+    /// Letters may be either case; returns `None` for anything else.
+    pub(crate) fn parse(rule: &[u8]) -> Option<Rule> {
+        let slash = rule.iter().position(|&b| b == b'/')?;
+        let (birth, survival) = (&rule[..slash], &rule[slash + 1..]);
+        Some(Rule::new(
+            parse_counts(birth, b'B')?,
+            parse_counts(survival, b'S')?,
+        ))
+    }
+
+    pub(crate) fn next_alive(&self, alive: bool, neighbors: u32) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        neighbors < 32 && mask & (1 << neighbors) != 0
+    }
+}
+
+/// Parses one half of a rulestring: `prefix` (either case) followed by
+/// neighbor counts 0 through 8, returned as a mask.
+fn parse_counts(part: &[u8], prefix: u8) -> Option<u32> {
+    let (&first, digits) = part.split_first()?;
+    if first.to_ascii_uppercase() != prefix {
+        return None;
+    }
+    digits.iter().try_fold(0u32, |mask, &d| match d {
+        b'0'..=b'8' => Some(mask | 1 << (d - b'0')),
+        _ => None,
+    })
+}
+
+/// How neighbor lookups past the edge of the board are resolved.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BoundaryMode {
+    /// Off-board cells are dead.
+    Dead,
+    /// The board wraps around: the east edge borders the west edge and the
+    /// south edge borders the north edge.
+    Toroidal,
+    /// Off-board lookups mirror back onto the board: `x = -1` reads `x = 0`,
+    /// `x = width` reads `x = width - 1`, and likewise for rows.
+    Reflective,
+}
+
+impl BoundaryMode {
+    /// Maps a possibly off-board coordinate onto the board, or `None` if it reads
+    /// as a dead cell.
+    fn resolve(self, x: i32, y: i32, width: usize, height: usize) -> Option<usize> {
+        match self {
+            BoundaryMode::Dead => (x >= 0 && x < width as i32 && y >= 0 && y < height as i32)
+                .then(|| (y as usize) * width + (x as usize)),
+            BoundaryMode::Toroidal => {
+                let x = x.rem_euclid(width as i32) as usize;
+                let y = y.rem_euclid(height as i32) as usize;
+                Some(y * width + x)
+            }
+            BoundaryMode::Reflective => {
+                let x = x.clamp(0, width as i32 - 1) as usize;
+                let y = y.clamp(0, height as i32 - 1) as usize;
+                Some(y * width + x)
+            }
+        }
+    }
+}
+
+/// Returns (neighbor_count, array of neighbor cell types, count of types)
+pub(crate) fn get_neighbor_info(
+    grid: &[u8],
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    boundary: BoundaryMode,
+) -> (u32, [u8; 8], usize) {
+    let (types, count) = neighbor_types_at(grid, x, y, width, height, boundary, &MOORE);
+    (count as u32, types, count)
+}
+
+/// Returns the number of live cells within Chebyshev distance `radius` of
+/// `(x, y)`, not counting the cell itself.
+pub(crate) fn count_within(
+    grid: &[u8],
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    boundary: BoundaryMode,
+    radius: i32,
+) -> u32 {
+    let mut count = 0u32;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            if let Some(i) = boundary.resolve(x + dx, y + dy, width, height) {
+                if grid[i] != b' ' {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Offsets of all eight neighbors, in row-major order.
+pub(crate) const MOORE: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Offsets of the four diagonal neighbors.
+pub(crate) const DIAGONAL: [(i32, i32); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+/// Offsets of the four orthogonal neighbors.
+pub(crate) const ORTHOGONAL: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Returns the types of the live cells at up to eight `offsets` from `(x, y)`,
+/// and how many there are.
+pub(crate) fn neighbor_types_at(
+    grid: &[u8],
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    boundary: BoundaryMode,
+    offsets: &[(i32, i32)],
+) -> ([u8; 8], usize) {
+    let mut types = [0u8; 8];
+    let mut count = 0usize;
+    for &(dx, dy) in offsets.iter().take(8) {
+        if let Some(i) = boundary.resolve(x + dx, y + dy, width, height) {
+            if grid[i] != b' ' {
+                types[count] = grid[i];
+                count += 1;
+            }
+        }
+    }
+    (types, count)
+}
+
+/// How `get_dominant_type` picks a winner among equally common neighbor types.
+pub(crate) enum TieBreak<'a> {
+    /// Draw from the environment PRNG.
+    Random,
+    /// Consume caller-supplied bytes in order, each taken modulo the number of
+    /// tied types; once exhausted, pick the smallest tied byte.
+    Entropy { bytes: &'a Bytes, cursor: u32 },
+    /// Pick the smallest tied byte, so results replay exactly off-chain.
+    Smallest,
+    /// Pick the tied type with the most cells on the whole board, as counted
+    /// by cell byte before the generation began; equal populations go to the
+    /// smallest byte.
+    Seniority(&'a [u32; 256]),
+}
+
+/// How `get_dominant_type` scores each neighboring colony before comparing.
+pub(crate) enum Tally<'a> {
+    /// One point per neighbor.
+    Count,
+    /// One point per neighbor, plus `bonus` for `colony`, capped at 8.
+    Bonus { colony: u32, bonus: u32 },
+    /// Each neighbor scores its colony's weight, indexed by cell byte.
+    Weighted(&'a [u32; 256]),
+}
+
+/// How a newborn cell chooses its colony among its neighbors.
+pub(crate) struct Dominance<'a> {
+    pub(crate) tally: Tally<'a>,
+    pub(crate) tie: TieBreak<'a>,
+}
+
+impl Dominance<'_> {
+    /// Plain neighbor counts with PRNG tie-breaks, as `next_generation` uses.
+    pub(crate) fn random() -> Self {
+        Dominance {
+            tally: Tally::Count,
+            tie: TieBreak::Random,
+        }
+    }
+
+    /// Plain neighbor counts with ties going to the smallest byte.
+    pub(crate) fn deterministic() -> Self {
+        Dominance {
+            tally: Tally::Count,
+            tie: TieBreak::Smallest,
+        }
+    }
+}
+
+/// Returns the neighbor types sharing the top score under `tally`, in the
+/// order they were first seen, and how many there are.
+pub(crate) fn leading_types(types: &[u8], type_count: usize, tally: &Tally) -> ([u8; 8], usize) {
+    let mut counts: [(u8, u32); 8] = [(0, 0); 8];
+    let mut unique_count = 0usize;
+
+    for &t in &types[..type_count] {
+        if let Some(entry) = counts[..unique_count].iter_mut().find(|(c, _)| *c == t) {
+            entry.1 += 1;
+        } else {
+            counts[unique_count] = (t, 1);
+            unique_count += 1;
+        }
+    }
+
+    match tally {
+        Tally::Count => {}
+        Tally::Bonus { colony, bonus } => {
+            for entry in counts[..unique_count].iter_mut() {
+                if entry.0 as u32 == *colony {
+                    entry.1 = entry.1.saturating_add(*bonus).min(8);
+                }
+            }
+        }
+        Tally::Weighted(weights) => {
+            for entry in counts[..unique_count].iter_mut() {
+                entry.1 = entry.1.saturating_mul(weights[entry.0 as usize]);
+            }
+        }
+    }
+
+    let max_count = counts[..unique_count]
+        .iter()
+        .map(|&(_, n)| n)
+        .max()
+        .unwrap_or(0);
+
+    let mut winners: [u8; 8] = [0; 8];
+    let mut winner_count = 0usize;
+    for &(t, n) in &counts[..unique_count] {
+        if n == max_count {
+            winners[winner_count] = t;
+            winner_count += 1;
+        }
+    }
+
+    (winners, winner_count)
+}
+
+/// Returns the smallest of `types`, or `O` if there are none.
+fn smallest(types: &[u8]) -> u8 {
+    types.iter().copied().min().unwrap_or(b'O')
+}
+
+/// Returns the dominant cell type among neighbors, scored and tie-broken per
+/// `dominance`.
+pub(crate) fn get_dominant_type(
+    env: &Env,
+    types: &[u8],
+    type_count: usize,
+    dominance: &mut Dominance,
+) -> u8 {
+    if type_count == 0 {
+        return b'O';
+    }
+    if type_count == 1 {
+        return types[0];
+    }
+
+    let (winners, winner_count) = leading_types(types, type_count, &dominance.tally);
+
+    if winner_count == 1 {
+        return winners[0];
+    }
+
+    match &mut dominance.tie {
+        TieBreak::Random => {
+            let index = env.prng().gen_range::<u64>(0..winner_count as u64) as usize;
+            winners[index]
+        }
+        TieBreak::Entropy { bytes, cursor } => match bytes.get(*cursor) {
+            Some(b) => {
+                *cursor += 1;
+                winners[b as usize % winner_count]
+            }
+            None => smallest(&winners[..winner_count]),
+        },
+        TieBreak::Smallest => smallest(&winners[..winner_count]),
+        TieBreak::Seniority(population) => {
+            let tied = &winners[..winner_count];
+            let largest = tied.iter().map(|&t| population[t as usize]).max();
+            let senior = tied
+                .iter()
+                .copied()
+                .filter(|&t| Some(population[t as usize]) == largest);
+            senior.min().unwrap_or(b'O')
+        }
+    }
+}
+
+/// Returns the next state of the cell at `(x, y)`: its own byte if it
+/// survives, a newborn's colony chosen per `dominance` if it is born, or a
+/// space if it is (or stays) dead. Every rule variant built on birth/survival
+/// masks goes through here.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transition(
+    env: &Env,
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    rule: Rule,
+    boundary: BoundaryMode,
+    dominance: &mut Dominance,
+) -> u8 {
+    let current_char = grid[y * width + x];
+    let cell_alive = current_char != b' ';
+    let (neighbor_types, type_count) = neighbor_types_at(
+        grid,
+        x as i32,
+        y as i32,
+        width,
+        height,
+        boundary,
+        rule.neighborhood.offsets(),
+    );
+
+    if !rule.next_alive(cell_alive, type_count as u32) {
+        b' '
+    } else if cell_alive {
+        current_char
+    } else {
+        get_dominant_type(env, &neighbor_types[..type_count], type_count, dominance)
+    }
+}
+
+/// Writes the next generation of a `width`x`height` grid under `rule` into `next`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn step_grid(
+    env: &Env,
+    grid: &[u8],
+    next: &mut [u8],
+    width: usize,
+    height: usize,
+    rule: Rule,
+    boundary: BoundaryMode,
+    dominance: &mut Dominance,
+) {
+    for y in 0..height {
+        for x in 0..width {
+            next[y * width + x] =
+                transition(env, grid, width, height, x, y, rule, boundary, dominance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn next_cell(env: &Env, grid: &[u8], width: usize, x: usize, y: usize, rule: Rule) -> u8 {
+        let height = grid.len() / width;
+        transition(
+            env,
+            grid,
+            width,
+            height,
+            x,
+            y,
+            rule,
+            BoundaryMode::Dead,
+            &mut Dominance::random(),
+        )
+    }
+
+    #[test]
+    fn test_transition_survival_and_death() {
+        let env = Env::default();
+        let blinker = b"   XXX   ";
+        assert_eq!(next_cell(&env, blinker, 3, 1, 1, Rule::CONWAY), b'X');
+        assert_eq!(next_cell(&env, blinker, 3, 0, 1, Rule::CONWAY), b' ');
+    }
+
+    #[test]
+    fn test_transition_birth_takes_dominant_type() {
+        let env = Env::default();
+        let grid = b"X X   O  ";
+        assert_eq!(next_cell(&env, grid, 3, 1, 1, Rule::CONWAY), b'X');
+        assert_eq!(next_cell(&env, grid, 3, 1, 2, Rule::CONWAY), b' ');
+    }
+
+    #[test]
+    fn test_transition_custom_masks() {
+        let env = Env::default();
+        let seeds = Rule::new(1 << 2, 0);
+        let grid = b"O O      ";
+        assert_eq!(next_cell(&env, grid, 3, 1, 0, seeds), b'O');
+        assert_eq!(next_cell(&env, grid, 3, 0, 0, seeds), b' ');
+    }
+
+    #[test]
+    fn test_parse_rulestring() {
+        let highlife = Rule::parse(b"B36/S23").unwrap();
+        assert_eq!((highlife.birth, highlife.survival), (0b1001000, 0b1100));
+        let seeds = Rule::parse(b"b2/s").unwrap();
+        assert_eq!((seeds.birth, seeds.survival), (0b100, 0));
+        assert!(Rule::parse(b"B3S23").is_none());
+        assert!(Rule::parse(b"B39/S23").is_none());
+        assert!(Rule::parse(b"S23/B3").is_none());
+    }
+}